@@ -0,0 +1,88 @@
+//! Board storage benchmarks, through the public [Board] API.
+//!
+//! Compare the dense grid with the HashMap tiles used to be stored in:
+//!
+//! ```sh
+//! cargo bench --bench storage --features hashmap-tiles -- --save-baseline hashmap
+//! cargo bench --bench storage -- --baseline hashmap
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use drg::board::board::{Board, BoardConfig};
+use drg::board::coordinates::Coordinates;
+
+fn board() -> Board {
+    Board::from_seed(3, &BoardConfig::default())
+}
+
+fn walkable(board: &Board) -> Vec<Coordinates> {
+    board
+        .sorted_coordinates()
+        .into_iter()
+        .filter(|coords| {
+            board
+                .get(*coords)
+                .is_some_and(|tile| tile.kind.is_walkable())
+        })
+        .collect()
+}
+
+fn lookups(c: &mut Criterion) {
+    let board = board();
+    let coords = board.sorted_coordinates();
+    c.bench_function("get", |b| {
+        b.iter(|| {
+            coords
+                .iter()
+                .filter(|coords| board.get(**coords).is_some_and(|t| t.kind.is_opaque()))
+                .count()
+        })
+    });
+    c.bench_function("neighbours", |b| {
+        b.iter(|| {
+            coords
+                .iter()
+                .map(|coords| board.neighbours(*coords).len())
+                .sum::<usize>()
+        })
+    });
+}
+
+fn line_of_sight(c: &mut Criterion) {
+    let board = board();
+    let coords = board.sorted_coordinates();
+    c.bench_function("line_of_sight", |b| {
+        b.iter(|| {
+            let mut visible = 0;
+            for from in coords.iter().step_by(23) {
+                for to in coords.iter().step_by(3) {
+                    visible += board.line_of_sight(*from, *to, |_| false) as usize;
+                }
+            }
+            visible
+        })
+    });
+}
+
+fn pathfinding(c: &mut Criterion) {
+    let board = board();
+    let walkable = walkable(&board);
+    let pairs: Vec<(Coordinates, Coordinates)> = walkable
+        .iter()
+        .step_by(17)
+        .zip(walkable.iter().rev().step_by(13))
+        .map(|(from, to)| (*from, *to))
+        .collect();
+    c.bench_function("path", |b| {
+        b.iter(|| {
+            pairs
+                .iter()
+                .filter_map(|(from, to)| board.path(black_box(*from), black_box(*to)))
+                .map(|path| path.len())
+                .sum::<usize>()
+        })
+    });
+}
+
+criterion_group!(benches, lookups, line_of_sight, pathfinding);
+criterion_main!(benches);
@@ -0,0 +1,272 @@
+//! Maps are written one line per offset row, one character per tile, like so:
+//!
+//! ```text
+//! ; Lines starting with a semicolon are comments.
+//! # # # # #
+//!  # . D . #
+//! # . ~ ~ . #
+//!  # E . $ #
+//! ```
+//!
+//! Tiles are separated by a space, and odd rows are indented by one more space so the text
+//! looks like the board. A space where a tile should be means there is no tile there.
+//! Characters are read using [Coordinates::from_offset], so the first character of the first
+//! line is at offset 0, 0.
+use std::fmt;
+use std::str::FromStr;
+
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::occupant::OccupantKind;
+use super::tile::{Tile, TileKind};
+
+/// Character of every [TileKind].
+const TERRAIN: [(char, TileKind); 8] = [
+    ('.', TileKind::Floor),
+    (':', TileKind::Dirt),
+    ('#', TileKind::Rock),
+    ('$', TileKind::Mineral),
+    ('*', TileKind::Nitra),
+    ('^', TileKind::Lava),
+    ('~', TileKind::Water),
+    ('o', TileKind::Pit),
+];
+
+/// Character of every spawn point. Spawn points always are on [Floor][TileKind::Floor].
+const SPAWNS: [(char, OccupantKind); 4] = [
+    ('D', OccupantKind::Dwarf),
+    ('E', OccupantKind::Enemy),
+    ('I', OccupantKind::Item),
+    ('X', OccupantKind::Objective),
+];
+
+/// Why a map could not be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Character isn't a terrain or a spawn point.
+    UnknownTile(char),
+    /// Tiles must be separated by a space.
+    MissingSeparator(char),
+    /// Odd rows must start with a space.
+    MissingIndent(char),
+}
+
+/// Error while reading a map, with the line and column where it happened. Both start at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::UnknownTile(c) => write!(f, "unknown tile '{}'", c),
+            ParseErrorKind::MissingSeparator(c) => {
+                write!(f, "expected a space between tiles, found '{}'", c)
+            }
+            ParseErrorKind::MissingIndent(c) => {
+                write!(f, "expected odd row to start with a space, found '{}'", c)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for Board {
+    type Err = ParseError;
+
+    /// Read a map written in the format described in the [module][self] documentation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut board = Board::default();
+
+        let rows = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.starts_with(';'));
+        for (y, (index, line)) in rows.enumerate() {
+            let y = y as i32;
+            let indent = (y & 1) as usize;
+
+            for (column, c) in line.chars().enumerate() {
+                let error = |kind| ParseError {
+                    line: index + 1,
+                    column: column + 1,
+                    kind,
+                };
+
+                if column < indent {
+                    if c != ' ' {
+                        return Err(error(ParseErrorKind::MissingIndent(c)));
+                    }
+                    continue;
+                }
+                let position = column - indent;
+                if position % 2 == 1 {
+                    if c != ' ' {
+                        return Err(error(ParseErrorKind::MissingSeparator(c)));
+                    }
+                    continue;
+                }
+                if c == ' ' {
+                    continue;
+                }
+
+                let coords = Coordinates::from_offset((position / 2) as i32, y);
+                if let Some((_, kind)) = TERRAIN.iter().find(|(symbol, _)| *symbol == c) {
+                    board.tiles.insert(coords, Tile::new(*kind));
+                } else if let Some((_, kind)) = SPAWNS.iter().find(|(symbol, _)| *symbol == c) {
+                    board.tiles.insert(coords, Tile::new(TileKind::Floor));
+                    board.spawns.insert(coords, *kind);
+                } else {
+                    return Err(error(ParseErrorKind::UnknownTile(c)));
+                }
+            }
+        }
+
+        Ok(board)
+    }
+}
+
+impl fmt::Display for Board {
+    /// Write the map in the format described in the [module][self] documentation.
+    ///
+    /// Occupants are not part of the map, and tiles with negative offset coordinates are left out.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offsets: Vec<(i32, i32)> = self
+            .tiles
+            .keys()
+            .map(|coords| coords.to_offset())
+            .filter(|(x, y)| *x >= 0 && *y >= 0)
+            .collect();
+        let width = offsets.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+        let height = offsets.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
+
+        for y in 0..height {
+            let mut row = String::new();
+            if y & 1 != 0 {
+                row.push(' ');
+            }
+            for x in 0..width {
+                if x != 0 {
+                    row.push(' ');
+                }
+                row.push(self.symbol(Coordinates::from_offset(x, y)));
+            }
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Board {
+    /// Character used to write a tile.
+    fn symbol(&self, coords: Coordinates) -> char {
+        let tile = match self.get(coords) {
+            Some(tile) => tile,
+            None => return ' ',
+        };
+        if let Some(spawn) = self.spawns.get(&coords) {
+            if tile.kind == TileKind::Floor {
+                if let Some((symbol, _)) = SPAWNS.iter().find(|(_, kind)| kind == spawn) {
+                    return *symbol;
+                }
+            }
+        }
+        TERRAIN
+            .iter()
+            .find(|(_, kind)| *kind == tile.kind)
+            .map(|(symbol, _)| *symbol)
+            .expect("every terrain should have a symbol")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::board::BoardConfig;
+
+    const MAP: &str = "\
+; A small cave.
+# # # # #
+ # . D . #
+# . ~ ~ . #
+ # E . $ #
+#   : o * ^
+";
+
+    #[test]
+    fn parse() {
+        let board: Board = MAP.parse().unwrap();
+
+        assert_eq!(board.tiles.keys().count(), 26);
+        assert_eq!(
+            board.get(Coordinates::from_offset(2, 2)).unwrap().kind,
+            TileKind::Water
+        );
+        assert_eq!(
+            board.get(Coordinates::from_offset(5, 4)).unwrap().kind,
+            TileKind::Lava
+        );
+        assert!(board.get(Coordinates::from_offset(1, 4)).is_none());
+        assert_eq!(
+            board.spawns.get(&Coordinates::from_offset(2, 1)),
+            Some(&OccupantKind::Dwarf)
+        );
+        assert_eq!(
+            board.get(Coordinates::from_offset(1, 3)).unwrap().kind,
+            TileKind::Floor
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let board: Board = MAP.parse().unwrap();
+        let written = board.to_string();
+
+        assert_eq!(
+            written,
+            MAP.lines().skip(1).collect::<Vec<_>>().join("\n") + "\n"
+        );
+        assert_eq!(written.parse::<Board>().unwrap(), board);
+    }
+
+    #[test]
+    fn round_trip_generated() {
+        let board = Board::from_seed(12, &BoardConfig::default());
+
+        assert_eq!(board.to_string().parse::<Board>().unwrap(), board);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "# #\n# #".parse::<Board>(),
+            Err(ParseError {
+                line: 2,
+                column: 1,
+                kind: ParseErrorKind::MissingIndent('#'),
+            })
+        );
+        assert_eq!(
+            "; comment\n# #\n # #.".parse::<Board>(),
+            Err(ParseError {
+                line: 3,
+                column: 5,
+                kind: ParseErrorKind::MissingSeparator('.'),
+            })
+        );
+        assert_eq!(
+            "# ? #".parse::<Board>(),
+            Err(ParseError {
+                line: 1,
+                column: 3,
+                kind: ParseErrorKind::UnknownTile('?'),
+            })
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use super::board::Board;
+use super::coordinates::{Coordinates, Crossing, FloatCoordinates};
+use super::edge::EdgeKind;
+use super::occupant::Occupant;
+
+/// Every segment making up a beam `width` tiles wide, side by side, one tile apart.
+fn beam_segments(
+    from: Coordinates,
+    to: Coordinates,
+    width: u32,
+) -> Vec<(FloatCoordinates, FloatCoordinates)> {
+    let (a, b) = (FloatCoordinates::from(from), FloatCoordinates::from(to));
+    // Sideways on screen, one tile long, back in axial coordinates.
+    let (x, y) = (
+        b.q - a.q + (b.r - a.r) / 2.0,
+        (b.r - a.r) * 3f64.sqrt() / 2.0,
+    );
+    let length = x.hypot(y);
+    if length == 0.0 {
+        return vec![(a, b)];
+    }
+    let (x, y) = (-y / length, x / length);
+    let side = FloatCoordinates {
+        q: x - y / 3f64.sqrt(),
+        r: y * 2.0 / 3f64.sqrt(),
+    };
+    let width = width.max(1);
+    (0..width)
+        .map(|i| {
+            let offset = f64::from(i) - f64::from(width - 1) / 2.0;
+            let shift = FloatCoordinates {
+                q: side.q * offset,
+                r: side.r * offset,
+            };
+            (a + shift, b + shift)
+        })
+        .collect()
+}
+
+impl Coordinates {
+    /// Every tile the segment between the centers of both tiles touches, from this one to the
+    /// target, both included. Where the segment goes right between two tiles, both are in.
+    pub fn supercover(self, target: Coordinates) -> Vec<Coordinates> {
+        self.thick_line(target, 1)
+    }
+
+    /// Every tile touched by a [supercover][Coordinates::supercover] line `width` tiles wide,
+    /// ordered from this tile. A width of 1 is the supercover line itself.
+    pub fn thick_line(self, target: Coordinates, width: u32) -> Vec<Coordinates> {
+        let mut tiles: Vec<(Coordinates, f64)> = vec![];
+        for (a, b) in beam_segments(self, target, width) {
+            for crossing in a.crossings(b) {
+                if let Crossing::Tile { coords, enter, .. } = crossing {
+                    tiles.push((coords, enter));
+                }
+            }
+        }
+        ordered(tiles)
+    }
+}
+
+/// Tiles ordered by where a line first touches them, without duplicates.
+fn ordered(mut tiles: Vec<(Coordinates, f64)>) -> Vec<Coordinates> {
+    tiles.sort_by(|(a, a_enter), (b, b_enter)| {
+        a_enter.total_cmp(b_enter).then((a.r, a.q).cmp(&(b.r, b.q)))
+    });
+    let mut seen = std::collections::HashSet::new();
+    tiles
+        .into_iter()
+        .filter_map(|(coords, _)| seen.insert(coords).then_some(coords))
+        .collect()
+}
+
+impl Board {
+    /// Every tile a beam `width` tiles wide hits, going from `from` toward `to`, see
+    /// [Coordinates::thick_line]. The shooter's tile is left out.
+    ///
+    /// Opaque tiles, tiles holding an occupant for which `blocks` is true, and walls or closed
+    /// doors are blockers. The beam goes through `pierce` of them, and stops at the next one,
+    /// which is hit unless it is a wall or door. It always stops at the edge of the board. Each
+    /// line of a thick beam is stopped on its own.
+    pub fn beam<F>(
+        &self,
+        from: Coordinates,
+        to: Coordinates,
+        width: u32,
+        pierce: u32,
+        blocks: F,
+    ) -> Vec<Coordinates>
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        let mut hit: Vec<(Coordinates, f64)> = vec![];
+        for (a, b) in beam_segments(from, to, width) {
+            let mut pierce = pierce;
+            for crossing in a.crossings(b) {
+                let blocker = match crossing {
+                    Crossing::Side { from, to, .. } => self
+                        .edge_between(from, to)
+                        .is_some_and(EdgeKind::blocks_movement),
+                    Crossing::Tile { coords, .. } if coords == from => false,
+                    Crossing::Tile { coords, enter, .. } => {
+                        let Some(tile) = self.get(coords) else { break };
+                        hit.push((coords, enter));
+                        tile.kind.is_opaque() || self.occupants_at(coords).iter().any(&blocks)
+                    }
+                };
+                if blocker {
+                    if pierce == 0 {
+                        break;
+                    }
+                    pierce -= 1;
+                }
+            }
+        }
+        ordered(hit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::direction::Direction;
+    use crate::board::occupant::OccupantKind;
+    use crate::board::outline::Outline;
+    use crate::board::tile::TileKind;
+
+    const ORIGIN: Coordinates = Coordinates { q: 0, r: 0 };
+
+    #[test]
+    fn supercover() {
+        // Straight along a row, only that row.
+        let target = Coordinates { q: 4, r: 0 };
+        let expected: Vec<Coordinates> = (0..=4).map(|q| Coordinates { q, r: 0 }).collect();
+        assert_eq!(ORIGIN.supercover(target), expected);
+
+        // Toward a diagonal, right between two tiles.
+        let diagonal = ORIGIN.diagonal(Direction::Right);
+        assert_eq!(
+            ORIGIN.supercover(diagonal),
+            vec![
+                ORIGIN,
+                ORIGIN + Direction::Right,
+                ORIGIN + Direction::BottomRight,
+                diagonal
+            ]
+        );
+
+        // Every step is to a neighbour, and the rounded center line is covered.
+        for target in ORIGIN.ring(5) {
+            let line = ORIGIN.supercover(target);
+            assert_eq!((line[0], *line.last().unwrap()), (ORIGIN, target));
+            assert!(line.windows(2).all(|pair| pair[0].distance(pair[1]) <= 1));
+            assert!((0..=5).all(|i| {
+                let t = f64::from(i) / 5.0;
+                let point = FloatCoordinates {
+                    q: f64::from(target.q) * t,
+                    r: f64::from(target.r) * t,
+                };
+                line.contains(&Coordinates::round(point))
+            }));
+        }
+    }
+
+    #[test]
+    fn thick_lines() {
+        let target = Coordinates { q: 4, r: 0 };
+        assert_eq!(ORIGIN.thick_line(target, 1), ORIGIN.supercover(target));
+        let wide = ORIGIN.thick_line(target, 3);
+        // Rows on both sides are staggered, so each has one more tile touched.
+        assert_eq!(wide.len(), 17);
+        assert!(wide.iter().all(|coords| coords.r.abs() <= 1));
+        assert!(ORIGIN.supercover(target).iter().all(|c| wide.contains(c)));
+        assert_eq!(ORIGIN.thick_line(ORIGIN, 3), vec![ORIGIN]);
+    }
+
+    #[test]
+    fn piercing() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 8,
+            height: 3,
+        });
+        let start = Coordinates::from_offset(0, 1);
+        let at = |q| start + Coordinates { q, r: 0 };
+        let enemies = |occupant: &Occupant| occupant.kind == OccupantKind::Enemy;
+        board.place(at(2), OccupantKind::Enemy).unwrap();
+        board.place(at(3), OccupantKind::Enemy).unwrap();
+
+        // Stopped by the first enemy, which is hit.
+        assert_eq!(board.beam(start, at(5), 1, 0, enemies), vec![at(1), at(2)]);
+        assert_eq!(
+            board.beam(start, at(5), 1, 1, enemies),
+            vec![at(1), at(2), at(3)]
+        );
+        assert_eq!(board.beam(start, at(5), 1, 2, enemies).len(), 5);
+
+        // Walls stop the beam before the tile behind them.
+        board.set_edge(at(1), Direction::Right, Some(EdgeKind::Wall));
+        assert_eq!(board.beam(start, at(5), 1, 0, enemies), vec![at(1)]);
+        board.set_edge(at(1), Direction::Right, None);
+
+        // Rock counts as a blocker too.
+        board.set(at(4), TileKind::Rock);
+        assert_eq!(board.beam(start, at(5), 1, 2, enemies).last(), Some(&at(4)));
+    }
+}
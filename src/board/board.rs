@@ -1,98 +1,431 @@
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use super::{coordinates::Coordinates, tile::Tile};
+use super::coordinates::Coordinates;
+use super::edge::{Edge, EdgeKind};
+use super::event::{BoardEvent, EventQueue};
+use super::generator::{connect, Generator, Noise, MAX_ATTEMPTS};
+#[cfg(not(feature = "hashmap-tiles"))]
+use super::grid::TileGrid;
+#[cfg(feature = "hashmap-tiles")]
+use super::hash_grid::HashTileGrid as TileGrid;
+use super::layout::Layout;
+use super::occupant::{Occupancy, OccupancyError, Occupant, OccupantKind};
+use super::outline::Outline;
+use super::tile::{Tile, TileKind, CLIMB_COST, MAX_STEP};
+use super::zone::Zone;
 use crate::board::direction::Direction;
 use crate::board::shape::Shape;
 use priority_queue::PriorityQueue;
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
+use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::pixels::Color;
+use sdl2::rect::Point;
 use sdl2::render::Canvas;
 use sdl2::render::RenderTarget;
+use serde::{Deserialize, Serialize};
+
+/// Parameters used to generate a [Board].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardConfig {
+    /// Which tiles the board is made of.
+    pub outline: Outline,
+    /// Chance for a generated tile to be an obstacle.
+    pub obstacle_ratio: f64,
+}
+
+impl Default for BoardConfig {
+    /// The [canonical][Outline::canonical] board, with 8% of obstacles.
+    fn default() -> Self {
+        BoardConfig {
+            outline: Outline::canonical(),
+            obstacle_ratio: 0.08,
+        }
+    }
+}
+
+/// Turn a mission seed string into a numeric seed.
+///
+/// Uses FNV-1a rather than the std hasher, whose output is not guaranteed to be stable
+/// between Rust releases, so a mission seed gives the same board everywhere.
+pub fn mission_seed(seed: &str) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    seed.bytes().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
 
 /// A game board containing a reference to each of its [Tiles][Tile].
-#[derive(Debug)]
+///
+/// Fields added after the first [save format][super::save] version must have a default value,
+/// so older saves keep loading.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Board {
-    pub tiles: HashMap<Coordinates, Tile>,
+    pub tiles: TileGrid,
+    /// Who stands where. Use the board's methods to change it, so terrain gets checked.
+    #[serde(default)]
+    pub occupants: Occupancy,
+    /// Where occupants of each kind enter the board.
+    #[serde(default, with = "super::save::by_coordinates")]
+    pub spawns: HashMap<Coordinates, OccupantKind>,
+    /// Named groups of tiles, see [Board::define_zone].
+    #[serde(default)]
+    pub zones: Vec<Zone>,
+    /// Walls and doors between tiles, see [Board::set_edge].
+    #[serde(default, with = "super::save::by_edge")]
+    pub edges: HashMap<Edge, EdgeKind>,
+    /// Changes not handled yet, see [Board::drain_events].
+    #[serde(skip)]
+    pub events: EventQueue,
+}
+
+impl PartialEq for Board {
+    /// Pending events are left out, they are not part of the board's state.
+    fn eq(&self, other: &Self) -> bool {
+        self.tiles == other.tiles
+            && self.occupants == other.occupants
+            && self.spawns == other.spawns
+            && self.zones == other.zones
+            && self.edges == other.edges
+    }
 }
 
 impl Board {
-    /// Initialize a new board with
+    /// Initialize a new canonical board from a random seed.
     pub fn new() -> Self {
-        let mut rng = SmallRng::from_entropy();
-        let mut tiles: HashMap<Coordinates, Tile> = HashMap::new();
-        for x in 0..15 {
-            for y in 0..15 {
-                // remove last piece from every odd row to get nice square board.
-                if x == 14 && y & 1 != 0 {
-                    continue;
-                }
-                // Remove pieces to be iso with the canonical board.
-                if (x == 0 || x == 14) && (y == 4 || y == 10) {
-                    continue;
-                }
+        Self::from_seed(SmallRng::from_entropy().gen(), &BoardConfig::default())
+    }
 
-                // Generated tiles have 8% chance of being obstacles.
-                let mut free = rng.gen_bool(0.92);
-                if x == 0 && y == 0 {
-                    free = false;
-                }
+    /// Initialize a new board with the [Noise] generator.
+    /// The same seed and config always give the same board.
+    pub fn from_seed(seed: u64, config: &BoardConfig) -> Self {
+        Self::generate(
+            seed,
+            config,
+            &Noise {
+                obstacle_ratio: config.obstacle_ratio,
+            },
+        )
+    }
+
+    /// Initialize a new board from a mission seed, see [mission_seed].
+    pub fn from_mission_seed(seed: &str, config: &BoardConfig) -> Self {
+        Self::from_seed(mission_seed(seed), config)
+    }
+
+    /// Initialize a new board using the given [Generator].
+    ///
+    /// Every free tile of the result can reach every other free tile: disconnected caves are
+    /// joined by carving corridors, and the map is rerolled if the generator left nothing open.
+    ///
+    /// After [MAX_ATTEMPTS] rolls that could not be connected, the last one is returned as is: it
+    /// may be disconnected, or have no free tile at all, when the outline itself is split in
+    /// several parts. Check with [Board::is_connected].
+    pub fn generate<G>(seed: u64, config: &BoardConfig, generator: &G) -> Self
+    where
+        G: Generator + ?Sized,
+    {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut attempts = 1;
 
-                tiles.insert(Coordinates::from_offset(x, y), Tile::new(free));
+        loop {
+            let mut board = Self::from_outline(&config.outline);
+            generator.generate(&mut board, &mut rng);
+            if connect(&mut board) || attempts == MAX_ATTEMPTS {
+                // Carving the cave isn't news to anyone.
+                board.events.clear();
+                return board;
             }
+            attempts += 1;
         }
-        Board { tiles }
+    }
+
+    /// Board with every tile of the outline, all free.
+    pub fn from_outline(outline: &Outline) -> Self {
+        let tiles: TileGrid = outline
+            .coordinates()
+            .into_iter()
+            .map(|coords| (coords, Tile::new(TileKind::Floor)))
+            .collect();
+
+        Board {
+            tiles,
+            occupants: Occupancy::default(),
+            spawns: HashMap::new(),
+            zones: vec![],
+            edges: HashMap::new(),
+            events: EventQueue::default(),
+        }
+    }
+
+    /// Coordinates of every tile, sorted by axial row then column.
+    pub fn sorted_coordinates(&self) -> Vec<Coordinates> {
+        let mut coords: Vec<Coordinates> = self.tiles.keys().collect();
+        coords.sort_by_key(|coords| (coords.r, coords.q));
+        coords
+    }
+
+    /// Split the free tiles into groups that can reach each other.
+    /// Biggest group comes first.
+    pub fn regions(&self) -> Vec<Vec<Coordinates>> {
+        let mut seen: HashSet<Coordinates> = HashSet::new();
+        let mut regions: Vec<Vec<Coordinates>> = vec![];
+
+        for start in self.sorted_coordinates() {
+            if !self.tiles[&start].kind.is_walkable() || seen.contains(&start) {
+                continue;
+            }
+
+            let mut region: Vec<Coordinates> = vec![];
+            let mut frontier: VecDeque<Coordinates> = VecDeque::from([start]);
+            seen.insert(start);
+            while let Some(current) = frontier.pop_front() {
+                region.push(current);
+                for next in self.neighbours(current) {
+                    if self.tiles[&next].kind.is_walkable() && seen.insert(next) {
+                        frontier.push_back(next);
+                    }
+                }
+            }
+            regions.push(region);
+        }
+
+        regions.sort_by_key(|region| Reverse(region.len()));
+        regions
+    }
+
+    /// True if every free tile can reach every other free tile.
+    pub fn is_connected(&self) -> bool {
+        self.regions().len() <= 1
     }
 
     /// Iterate over every tile and draw its base.
-    pub fn draw<T>(&self, canvas: &mut Canvas<T>)
+    pub fn draw<T>(&self, canvas: &mut Canvas<T>, layout: &Layout)
     where
         T: RenderTarget,
     {
         for (coords, tile) in self.tiles.iter() {
-            tile.draw(canvas, *coords, true);
+            tile.draw(canvas, layout, coords, true);
+        }
+        for zone in self.zones.iter() {
+            zone.draw(canvas, layout);
+        }
+        self.draw_edges(canvas, layout);
+        for (coords, kind) in self.spawns.iter() {
+            let center: Point = layout.hex_to_pixel(*coords);
+            canvas
+                .circle(
+                    center.x as i16,
+                    center.y as i16,
+                    (layout.size / 2.0) as i16,
+                    kind.color(),
+                )
+                .unwrap();
+        }
+        for (coords, occupant) in self.occupants.iter() {
+            let center: Point = layout.hex_to_pixel(coords);
+            canvas
+                .filled_circle(
+                    center.x as i16,
+                    center.y as i16,
+                    (layout.size / 2.0) as i16,
+                    occupant.kind.color(),
+                )
+                .unwrap();
         }
     }
 
     /// Get a reference to a tile on the board.
     pub fn get(&self, coords: Coordinates) -> Option<&Tile> {
-        match self.tiles.get(&coords.clone()) {
-            Some(tile) => Some(tile),
-            None => None,
+        self.tiles.get(&coords)
+    }
+
+    /// Iterate over every tile, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, &Tile)> + '_ {
+        self.tiles.iter()
+    }
+
+    /// Change what a tile on the gameboard is made of.
+    /// Emits [BoardEvent::TileChanged] if it was made of something else.
+    pub fn set(&mut self, coords: Coordinates, kind: TileKind) {
+        if let Some(tile) = self.tiles.get_mut(&coords) {
+            if tile.kind != kind {
+                self.events.push(BoardEvent::TileChanged {
+                    coords,
+                    old: tile.kind,
+                    new: kind,
+                });
+                tile.kind = kind;
+            }
+        }
+    }
+
+    /// Change the height of a tile's ground.
+    /// Emits [BoardEvent::HeightChanged] if it was at another height.
+    pub fn set_height(&mut self, coords: Coordinates, height: i32) {
+        if let Some(tile) = self.tiles.get_mut(&coords) {
+            if tile.height != height {
+                self.events.push(BoardEvent::HeightChanged {
+                    coords,
+                    old: tile.height,
+                    new: height,
+                });
+                tile.height = height;
+            }
         }
     }
 
+    /// Take every change made to the board since the last call, oldest first.
+    ///
+    /// Whoever owns the board should call this regularly and pass the events on to anything
+    /// derived from the board, like cached paths or the renderer.
+    pub fn drain_events(&mut self) -> Vec<BoardEvent> {
+        self.events.drain()
+    }
+
     /// Set tile as free on the gameboard.
     pub fn free(&mut self, coords: Coordinates) {
-        match self.tiles.get_mut(&coords.clone()) {
-            Some(tile) => tile.free = true,
-            None => (),
-        }
+        self.set(coords, TileKind::Floor);
     }
 
     /// Set a tile as filled on the gameboard.
     pub fn fill(&mut self, coords: Coordinates) {
-        match self.tiles.get_mut(&coords.clone()) {
-            Some(tile) => tile.free = false,
-            None => (),
+        self.set(coords, TileKind::Rock);
+    }
+
+    /// Put something new on a walkable tile. Only one unit can stand on a tile.
+    pub fn place(
+        &mut self,
+        coords: Coordinates,
+        kind: OccupantKind,
+    ) -> Result<Occupant, OccupancyError> {
+        self.check_placement(coords, kind, None)?;
+        let occupant = self.occupants.insert(coords, kind);
+        self.events
+            .push(BoardEvent::OccupantPlaced { occupant, coords });
+        Ok(occupant)
+    }
+
+    /// Move an occupant to another tile, following the same rules as [Board::place].
+    /// Returns where it came from.
+    pub fn move_occupant(
+        &mut self,
+        id: u32,
+        to: Coordinates,
+    ) -> Result<Coordinates, OccupancyError> {
+        let occupant = self.occupants.get(id).ok_or(OccupancyError::Unknown(id))?;
+        self.check_placement(to, occupant.kind, Some(id))?;
+        let from = self
+            .occupants
+            .relocate(id, to)
+            .expect("occupant should be on the board");
+        self.events
+            .push(BoardEvent::OccupantMoved { occupant, from, to });
+        Ok(from)
+    }
+
+    /// Take an occupant off the board, returning where it was.
+    pub fn remove_occupant(&mut self, id: u32) -> Option<(Coordinates, Occupant)> {
+        let (coords, occupant) = self.occupants.remove(id)?;
+        self.events
+            .push(BoardEvent::OccupantRemoved { occupant, coords });
+        Some((coords, occupant))
+    }
+
+    fn check_placement(
+        &self,
+        coords: Coordinates,
+        kind: OccupantKind,
+        moving: Option<u32>,
+    ) -> Result<(), OccupancyError> {
+        let tile = self.get(coords).ok_or(OccupancyError::OutOfBoard(coords))?;
+        if !tile.kind.is_walkable() {
+            return Err(OccupancyError::NotWalkable(coords));
+        }
+        if kind.is_unit() {
+            if let Some(unit) = self.occupants.unit_at(coords) {
+                if Some(unit.id) != moving {
+                    return Err(OccupancyError::Occupied(coords, unit));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Everything on a tile.
+    pub fn occupants_at(&self, coords: Coordinates) -> &[Occupant] {
+        self.occupants.at(coords)
+    }
+
+    /// Every occupant at most `radius` tiles away, closest first.
+    pub fn occupants_within(
+        &self,
+        center: Coordinates,
+        radius: u32,
+    ) -> Vec<(Coordinates, Occupant)> {
+        let mut found: Vec<(Coordinates, Occupant)> = self
+            .occupants
+            .iter()
+            .filter(|(coords, _)| center.distance(*coords) <= radius)
+            .collect();
+        found.sort_by_key(|(coords, occupant)| (center.distance(*coords), occupant.id));
+        found
+    }
+
+    /// Every enemy at most `radius` tiles away, closest first.
+    pub fn enemies_within(&self, center: Coordinates, radius: u32) -> Vec<(Coordinates, Occupant)> {
+        self.occupants_within(center, radius)
+            .into_iter()
+            .filter(|(_, occupant)| occupant.kind == OccupantKind::Enemy)
+            .collect()
+    }
+
+    /// Check line of sight between two tiles, through tiles that are not
+    /// [opaque][TileKind::is_opaque] and don't hold an occupant for which `blocks` is true.
+    /// Occupants at both ends never block. Heights count, see [Coordinates::strict_line].
+    pub fn line_of_sight<F>(&self, from: Coordinates, to: Coordinates, blocks: F) -> bool
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        from.strict_line_over(self, to, self.sight_check(from, to, blocks))
+    }
+
+    /// What sight goes through between `from` and `to`, for [Board::line_of_sight]: tiles that
+    /// are not opaque and don't hold an occupant for which `blocks` is true, except at both ends.
+    pub fn sight_check<'a, F>(
+        &'a self,
+        from: Coordinates,
+        to: Coordinates,
+        blocks: F,
+    ) -> impl Fn(Coordinates, Option<&Tile>) -> bool + 'a
+    where
+        F: Fn(&Occupant) -> bool + 'a,
+    {
+        move |coords, tile| {
+            let clear = matches!(tile, Some(tile) if !tile.kind.is_opaque());
+            clear
+                && (coords == from
+                    || coords == to
+                    || !self.occupants_at(coords).iter().any(&blocks))
         }
     }
 
-    /// Get a list of all the neighbouring tiles.
+    /// Get a list of all the neighbouring tiles that can be reached without crossing a wall or
+    /// closed door.
     pub fn neighbours(&self, coords: Coordinates) -> Vec<Coordinates> {
         let mut neighbours: Vec<Coordinates> = vec![];
 
-        for direction in [
-            Direction::TopRight,
-            Direction::Right,
-            Direction::BottomRight,
-            Direction::BottomLeft,
-            Direction::Left,
-            Direction::TopLeft,
-        ] {
+        for direction in Direction::ALL {
             let target = coords + direction;
 
-            if self.get(target).is_some() {
+            if self.get(target).is_some()
+                && !self
+                    .edge(coords, direction)
+                    .is_some_and(EdgeKind::blocks_movement)
+            {
                 neighbours.push(target)
             }
         }
@@ -101,39 +434,57 @@ impl Board {
     }
 
     /// Implementation of A* algo. The heuristic function is just Manhattan distance.
-    fn astar_search(
+    /// Entering a tile costs its [movement cost][TileKind::movement_cost].
+    /// Tiles holding an occupant for which `blocks` is true can't be crossed.
+    /// Steps between heights follow [Board::step_cost].
+    ///
+    /// Both returned vectors are indexed by [TileGrid::index].
+    fn astar_search<F>(
         &self,
         from: Coordinates,
         to: Coordinates,
-    ) -> (HashMap<Coordinates, Coordinates>, HashMap<Coordinates, i32>) {
-        let mut frontier = PriorityQueue::new();
-        frontier.push(from, 0);
-
-        let mut came_from: HashMap<Coordinates, Coordinates> = HashMap::new();
-        let mut cost_so_far: HashMap<Coordinates, i32> = HashMap::new();
+        blocks: F,
+        climbing: bool,
+    ) -> (Vec<Option<Coordinates>>, Vec<Option<i32>>)
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        let mut came_from: Vec<Option<Coordinates>> = vec![None; self.tiles.capacity()];
+        let mut cost_so_far: Vec<Option<i32>> = vec![None; self.tiles.capacity()];
 
-        cost_so_far.insert(from, 0);
+        let start = match self.tiles.index(from) {
+            Some(start) => start,
+            None => return (came_from, cost_so_far),
+        };
 
-        while !frontier.is_empty() {
-            let current = frontier.pop().expect("frontier should not be empty");
+        // The queue pops the highest priority first, so priorities are reversed.
+        let mut frontier = PriorityQueue::new();
+        frontier.push(from, Reverse(0));
+        cost_so_far[start] = Some(0);
 
-            if current.0 == to {
+        while let Some((current, _)) = frontier.pop() {
+            if current == to {
                 break;
             }
 
-            for next in self.neighbours(current.0).iter() {
-                if !self.get(*next).expect("neighbour should exist").free {
-                    continue;
-                }
+            let current_cost = self
+                .tiles
+                .index(current)
+                .and_then(|index| cost_so_far[index])
+                .expect("cost should have been in previous iteration");
+
+            for next in self.neighbours(current) {
+                let step_cost = match self.step_cost(current, next, climbing) {
+                    Some(cost) if !self.occupants_at(next).iter().any(&blocks) => cost,
+                    _ => continue,
+                };
 
-                let new_cost = cost_so_far
-                    .get(&current.0)
-                    .expect("cost should have been in previous iteration")
-                    + 1;
-                if cost_so_far.get(next).is_none() || &new_cost < cost_so_far.get(next).unwrap() {
-                    cost_so_far.insert(*next, new_cost);
-                    frontier.push(*next, next.distance(to));
-                    came_from.insert(*next, current.0);
+                let index = self.tiles.index(next).expect("neighbour should exist");
+                let new_cost = current_cost + step_cost;
+                if cost_so_far[index].is_none_or(|cost| new_cost < cost) {
+                    cost_so_far[index] = Some(new_cost);
+                    frontier.push(next, Reverse(new_cost + next.distance(to) as i32));
+                    came_from[index] = Some(current);
                 }
             }
         }
@@ -157,30 +508,85 @@ impl Board {
     }
 
     /// Path will call the astar_search function to compute the shortest path between from and to.
+    /// Occupants are ignored.
     ///
     /// If no path was found, returns None.
     pub fn path(&self, from: Coordinates, to: Coordinates) -> Option<Vec<Coordinates>> {
-        let (came_from, _cost_so_far) = self.astar_search(from, to);
+        self.path_with(from, to, |_| false)
+    }
 
-        let mut current = to;
-        let mut path: Vec<Coordinates> = vec![];
+    /// Like [Board::path], but tiles holding an occupant for which `blocks` is true can't be crossed.
+    pub fn path_with<F>(
+        &self,
+        from: Coordinates,
+        to: Coordinates,
+        blocks: F,
+    ) -> Option<Vec<Coordinates>>
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        self.find_path(from, to, blocks, false)
+    }
 
-        if came_from.get(&to).is_none() {
+    /// Like [Board::path_with], for someone with a climbing tool: any height can be climbed.
+    pub fn climbing_path<F>(
+        &self,
+        from: Coordinates,
+        to: Coordinates,
+        blocks: F,
+    ) -> Option<Vec<Coordinates>>
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        self.find_path(from, to, blocks, true)
+    }
+
+    /// Movement points needed to step from a tile to a neighbouring one: the
+    /// [movement cost][TileKind::movement_cost] of the destination, plus [CLIMB_COST] for each
+    /// level climbed up.
+    ///
+    /// None if the destination isn't walkable, or if it is more than [MAX_STEP] levels up or down
+    /// and `climbing` is false.
+    pub fn step_cost(&self, from: Coordinates, to: Coordinates, climbing: bool) -> Option<i32> {
+        let (from, to) = (self.get(from)?, self.get(to)?);
+        let rise = to.height - from.height;
+        if !to.kind.is_walkable() || (rise.abs() > MAX_STEP && !climbing) {
             return None;
         }
+        Some(to.kind.movement_cost() + rise.max(0) * CLIMB_COST)
+    }
+
+    fn find_path<F>(
+        &self,
+        from: Coordinates,
+        to: Coordinates,
+        blocks: F,
+        climbing: bool,
+    ) -> Option<Vec<Coordinates>>
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        let (came_from, _cost_so_far) = self.astar_search(from, to, blocks, climbing);
+
+        let came_from =
+            |coords: Coordinates| self.tiles.index(coords).and_then(|index| came_from[index]);
+
+        let mut current = to;
+        let mut path: Vec<Coordinates> = vec![];
+
+        came_from(to)?;
 
         while current != from {
             path.push(current);
-            current = *came_from
-                .get(&current)
-                .expect("came_from should always contain a value for current");
+            current =
+                came_from(current).expect("came_from should always contain a value for current");
         }
 
         path.reverse();
         Some(path)
     }
 
-    pub fn ghost_shape<T>(&self, shape: Shape, canvas: &mut Canvas<T>)
+    pub fn ghost_shape<T>(&self, shape: Shape, canvas: &mut Canvas<T>, layout: &Layout)
     where
         T: RenderTarget,
     {
@@ -189,12 +595,232 @@ impl Board {
 
         for coord in shape.tiles {
             if let Some(tile) = self.get(coord + shape.center) {
-                if tile.free {
-                    tile.mask(canvas, coord + shape.center, GREEN);
+                if tile.kind.is_walkable() {
+                    tile.mask(canvas, layout, coord + shape.center, GREEN);
                 } else {
-                    tile.mask(canvas, coord + shape.center, RED);
+                    tile.mask(canvas, layout, coord + shape.center, RED);
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_board() {
+        let config = BoardConfig::default();
+
+        assert_eq!(Board::from_seed(42, &config), Board::from_seed(42, &config));
+        assert_eq!(
+            Board::from_mission_seed("Hoxxes IV", &config),
+            Board::from_mission_seed("Hoxxes IV", &config)
+        );
+        assert_ne!(Board::from_seed(42, &config), Board::from_seed(43, &config));
+    }
+
+    #[test]
+    fn canonical_layout() {
+        let board = Board::from_seed(0, &BoardConfig::default());
+
+        assert_eq!(
+            board.tiles.keys().count(),
+            Outline::canonical().coordinates().len()
+        );
+        assert!(board.get(Coordinates::from_offset(14, 1)).is_none());
+        assert!(board.get(Coordinates::from_offset(0, 4)).is_none());
+        assert!(board.get(Coordinates::from_offset(14, 10)).is_none());
+        assert!(board.get(Coordinates::from_offset(14, 14)).is_some());
+    }
+
+    #[test]
+    fn obstacle_ratio() {
+        let config = BoardConfig {
+            outline: Outline::Rectangle {
+                width: 15,
+                height: 15,
+            },
+            obstacle_ratio: 0.0,
+        };
+        let board = Board::from_seed(7, &config);
+
+        assert_eq!(board.tiles.keys().count(), 15 * 15);
+        // Only the starting corner is an obstacle.
+        assert_eq!(
+            board
+                .tiles
+                .values()
+                .filter(|tile| !tile.kind.is_walkable())
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn path_avoids_costly_tiles() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 4,
+            height: 3,
+        });
+        let from = Coordinates::from_offset(0, 1);
+        let to = Coordinates::from_offset(3, 1);
+
+        assert_eq!(board.path(from, to).map(|path| path.len()), Some(3));
+
+        // Flooding the middle row makes the detour cheaper.
+        board.set(Coordinates::from_offset(1, 1), TileKind::Water);
+        board.set(Coordinates::from_offset(2, 1), TileKind::Water);
+        let path = board.path(from, to).expect("path should exist");
+        assert!(path
+            .iter()
+            .all(|coords| board.get(*coords).unwrap().kind == TileKind::Floor));
+
+        board.set(Coordinates::from_offset(1, 0), TileKind::Lava);
+        board.set(Coordinates::from_offset(1, 2), TileKind::Pit);
+        let path = board.path(from, to).expect("path should exist");
+        assert!(path.contains(&Coordinates::from_offset(1, 1)));
+    }
+
+    #[test]
+    fn every_outline() {
+        for outline in [
+            Outline::canonical(),
+            Outline::Hexagon { radius: 5 },
+            Outline::Parallelogram {
+                width: 6,
+                height: 4,
+            },
+        ] {
+            let board = Board::from_seed(
+                1,
+                &BoardConfig {
+                    outline: outline.clone(),
+                    obstacle_ratio: 0.2,
+                },
+            );
+            assert_eq!(board.tiles.keys().count(), outline.coordinates().len());
+            assert!(board.is_connected());
+            // Screen position and back.
+            let layout = Layout::default();
+            for coords in outline.coordinates() {
+                assert_eq!(layout.pick(layout.hex_to_pixel(coords)), coords);
+            }
+        }
+    }
+
+    #[test]
+    fn stable_mission_seed() {
+        assert_eq!(mission_seed(""), 0xcbf29ce484222325);
+        assert_eq!(mission_seed("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn climbing() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 5,
+            height: 1,
+        });
+        let tiles: Vec<Coordinates> = board.sorted_coordinates();
+        for (coords, height) in tiles.iter().zip([0, 1, 2, 1, 0]) {
+            board.set_height(*coords, height);
+        }
+
+        assert_eq!(
+            board.step_cost(tiles[0], tiles[1], false),
+            Some(1 + CLIMB_COST)
+        );
+        assert_eq!(board.step_cost(tiles[2], tiles[3], false), Some(1));
+        assert!(board.path(tiles[0], tiles[4]).is_some());
+
+        // Too steep to walk.
+        board.set_height(tiles[1], 0);
+        assert_eq!(board.step_cost(tiles[1], tiles[2], false), None);
+        assert_eq!(
+            board.step_cost(tiles[1], tiles[2], true),
+            Some(1 + 2 * CLIMB_COST)
+        );
+        assert!(board.path(tiles[0], tiles[4]).is_none());
+        assert_eq!(
+            board.climbing_path(tiles[0], tiles[4], |_| false),
+            Some(tiles[1..].to_vec())
+        );
+    }
+
+    #[test]
+    fn sight_over_heights() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 7,
+            height: 3,
+        });
+        let from = Coordinates::from_offset(0, 1);
+        let to = Coordinates::from_offset(6, 1);
+        let ridge: Vec<Coordinates> = (0..3).map(|y| Coordinates::from_offset(3, y)).collect();
+        for coords in ridge.iter() {
+            board.fill(*coords);
+        }
+        assert!(!board.line_of_sight(from, to, |_| false));
+
+        // Both ends are well above the wall.
+        board.set_height(from, 2);
+        board.set_height(to, 2);
+        assert!(board.line_of_sight(from, to, |_| false));
+        // Only one of them: the line still goes through the wall.
+        board.set_height(to, 0);
+        assert!(!board.line_of_sight(from, to, |_| false));
+
+        // Higher ground blocks sight even without walls.
+        board.set_height(from, 0);
+        for coords in ridge.iter() {
+            board.free(*coords);
+            board.set_height(*coords, 1);
+        }
+        assert!(!board.line_of_sight(from, to, |_| false));
+        board.set_height(from, 1);
+        board.set_height(to, 1);
+        assert!(board.line_of_sight(from, to, |_| false));
+    }
+
+    #[test]
+    fn walls_and_doors() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 7,
+            height: 3,
+        });
+        let from = Coordinates::from_offset(0, 1);
+        let to = Coordinates::from_offset(6, 1);
+        let wall: Vec<Coordinates> = (0..3).map(|y| Coordinates::from_offset(3, y)).collect();
+        for coords in wall.iter() {
+            for direction in [Direction::TopLeft, Direction::Left, Direction::BottomLeft] {
+                board.set_edge(*coords, direction, Some(EdgeKind::Wall));
+            }
+        }
+        assert!(board.path(from, to).is_none());
+        assert!(!board.line_of_sight(from, to, |_| false));
+        assert!(!board
+            .neighbours(wall[1])
+            .contains(&(wall[1] + Direction::Left)));
+        assert_eq!(
+            board.edge_between(wall[1] + Direction::Left, wall[1]),
+            Some(EdgeKind::Wall)
+        );
+
+        // A door is a wall until it opens.
+        let door = wall[1] + Direction::Left;
+        board.set_edge(door, Direction::Right, Some(EdgeKind::ClosedDoor));
+        assert!(board.path(from, to).is_none());
+        board.set_edge(door, Direction::Right, Some(EdgeKind::OpenDoor));
+        assert_eq!(board.path(from, to).unwrap().len(), 6);
+        assert!(board.line_of_sight(from, to, |_| false));
+
+        board.set_edge(wall[1], Direction::Left, None);
+        assert_eq!(board.edge(door, Direction::Right), None);
+        let events = board.drain_events();
+        assert_eq!(events.len(), 10);
+        assert!(matches!(
+            events.last(),
+            Some(BoardEvent::EdgeChanged { new: None, .. })
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::direction::Direction;
+use super::occupant::Occupant;
+
+/// Room left on the sides of a cone, so tiles right on a side are always in it.
+const ANGLE_EPSILON: f64 = 1e-6;
+
+/// Where a [Cone] points from its origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Facing {
+    /// Through the side of the origin facing that direction.
+    Side(Direction),
+    /// Through the corner between that direction and the next one clockwise, toward its
+    /// [diagonal][Coordinates::diagonal].
+    Corner(Direction),
+}
+
+impl Facing {
+    /// Angle on screen, in degrees clockwise from [Right][Direction::Right].
+    fn angle(self) -> f64 {
+        let (direction, offset) = match self {
+            Self::Side(direction) => (direction, 0.0),
+            Self::Corner(direction) => (direction, 30.0),
+        };
+        let index: i32 = direction.into();
+        f64::from(index - 1) * 60.0 + offset
+    }
+}
+
+/// Every tile in a wedge going out from an origin, like a flamethrower blast or a shotgun
+/// spread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cone {
+    pub origin: Coordinates,
+    pub facing: Facing,
+    /// Width of the cone, in degrees. 60 is the width of one side of a hex, 360 is every tile
+    /// around the origin.
+    pub arc: u32,
+    /// Farthest tiles in the cone, in steps from the origin.
+    pub range: u32,
+}
+
+impl Cone {
+    pub fn new(origin: Coordinates, facing: Facing, arc: u32, range: u32) -> Self {
+        Cone {
+            origin,
+            facing,
+            arc,
+            range,
+        }
+    }
+
+    /// Is the tile in the cone. Tiles whose center is right on a side of the cone are in it,
+    /// the origin never is.
+    pub fn contains(&self, coords: Coordinates) -> bool {
+        let offset = coords - self.origin;
+        let distance = self.origin.distance(coords);
+        if distance == 0 || distance > self.range {
+            return false;
+        }
+        if self.arc >= 360 {
+            return true;
+        }
+        // Centers on screen, with hexes one unit apart.
+        let x = f64::from(offset.q) + f64::from(offset.r) / 2.0;
+        let y = f64::from(offset.r) * 3f64.sqrt() / 2.0;
+        let angle = y.atan2(x).to_degrees() - self.facing.angle();
+        let angle = (angle + 180.0).rem_euclid(360.0) - 180.0;
+        angle.abs() <= f64::from(self.arc) / 2.0 + ANGLE_EPSILON
+    }
+
+    /// Every tile in the cone, ring after ring from the origin. Tiles don't have to exist on the
+    /// board.
+    pub fn tiles(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.origin
+            .spiral(self.range)
+            .filter(|coords| self.contains(*coords))
+    }
+}
+
+impl Board {
+    /// Every tile of the cone on the board, ring after ring from the origin.
+    pub fn cone(&self, cone: &Cone) -> Vec<Coordinates> {
+        cone.tiles()
+            .filter(|coords| self.get(*coords).is_some())
+            .collect()
+    }
+
+    /// Every tile of the cone the origin has a [line of sight][Board::line_of_sight] to, ring
+    /// after ring.
+    pub fn cone_in_sight<F>(&self, cone: &Cone, blocks: F) -> Vec<Coordinates>
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        let seen = self.field_of_view(cone.origin, Some(cone.range), blocks);
+        cone.tiles()
+            .filter(|coords| seen.contains(coords))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::edge::EdgeKind;
+    use crate::board::outline::Outline;
+    use crate::board::tile::TileKind;
+    use std::collections::HashSet;
+
+    const ORIGIN: Coordinates = Coordinates { q: 1, r: 2 };
+
+    #[test]
+    fn arcs() {
+        let cone = Cone::new(ORIGIN, Facing::Side(Direction::Right), 60, 3);
+        let tiles: Vec<Coordinates> = cone.tiles().collect();
+        assert_eq!(tiles.len(), 7);
+        assert_eq!(tiles[0], ORIGIN + Direction::Right);
+        // Diagonals lie right on the sides.
+        assert!(cone.contains(ORIGIN.diagonal(Direction::Right)));
+        assert!(cone.contains(ORIGIN.diagonal(Direction::TopRight)));
+        assert!(!cone.contains(ORIGIN));
+
+        // Wider cones hold the narrower ones.
+        for arc in [120, 180] {
+            let wide = Cone { arc, ..cone };
+            assert!(tiles.iter().all(|coords| wide.contains(*coords)));
+            assert!(wide.tiles().count() > tiles.len());
+        }
+        let half = Cone { arc: 180, ..cone };
+        assert_eq!(half.tiles().filter(|c| ORIGIN.distance(*c) == 1).count(), 3);
+
+        // Six narrow cones cover everything around the origin.
+        let mut covered = HashSet::new();
+        for direction in Direction::ALL {
+            covered.extend(Cone::new(ORIGIN, Facing::Side(direction), 60, 4).tiles());
+        }
+        let all = Cone::new(ORIGIN, Facing::Side(Direction::Left), 360, 4);
+        assert_eq!(covered, all.tiles().collect());
+        assert_eq!(covered.len(), 60);
+    }
+
+    #[test]
+    fn corners() {
+        let cone = Cone::new(ORIGIN, Facing::Corner(Direction::Right), 60, 2);
+        let tiles: HashSet<Coordinates> = cone.tiles().collect();
+        let expected = HashSet::from([
+            ORIGIN + Direction::Right,
+            ORIGIN + Direction::BottomRight,
+            ORIGIN.diagonal(Direction::Right),
+            ORIGIN + Direction::Right + Direction::Right,
+            ORIGIN + Direction::BottomRight + Direction::BottomRight,
+        ]);
+        assert_eq!(tiles, expected);
+
+        // Turning the facing turns the cone.
+        let turned = Cone::new(ORIGIN, Facing::Corner(Direction::BottomRight), 60, 2);
+        let rotated: HashSet<Coordinates> = tiles
+            .iter()
+            .map(|coords| (*coords - ORIGIN).rotate_clockwise() + ORIGIN)
+            .collect();
+        assert_eq!(turned.tiles().collect::<HashSet<_>>(), rotated);
+    }
+
+    #[test]
+    fn in_sight() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 7,
+            height: 7,
+        });
+        let origin = Coordinates::from_offset(4, 3);
+        let cone = Cone::new(origin, Facing::Side(Direction::Right), 120, 3);
+
+        // The cone goes past the right side of the board.
+        let on_board = board.cone(&cone);
+        assert!(on_board.iter().all(|coords| cone.contains(*coords)));
+        assert!(on_board.len() < cone.tiles().count());
+        let seen = board.cone_in_sight(&cone, |_| false);
+        assert_eq!(seen, on_board);
+
+        // A rock hides the tile behind it.
+        let rock = origin + Direction::BottomRight;
+        board.set(rock, TileKind::Rock);
+        let rocked = board.cone_in_sight(&cone, |_| false);
+        assert!(!rocked.contains(&rock));
+        assert!(!rocked.contains(&(rock + Direction::BottomRight)));
+        assert!(rocked.contains(&(origin + Direction::Right)));
+        board.set(rock, TileKind::Floor);
+
+        // Walls all around the front of the origin hide at least the tile right in front.
+        for direction in [
+            Direction::TopRight,
+            Direction::Right,
+            Direction::BottomRight,
+        ] {
+            board.set_edge(origin, direction, Some(EdgeKind::Wall));
+        }
+        let walled = board.cone_in_sight(&cone, |_| false);
+        assert!(!walled.contains(&(origin + Direction::Right)));
+        assert!(walled.len() < on_board.len());
+    }
+}
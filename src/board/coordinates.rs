@@ -1,68 +1,19 @@
-use super::{HEIGHT, HEX_SIZE, WIDTH};
-use crate::Direction;
+use crate::board::direction::Direction;
+use serde::{Deserialize, Serialize};
 use std::ops::{Add, Sub};
 
 use crate::board::board::Board;
+use crate::board::cube::Cube;
+use crate::board::edge::EdgeKind;
 use crate::board::tile::Tile;
-use sdl2::rect::Point;
-
-/// Tweak value between 1.732 and 2 to add or remove gap.
-const HEX_DIAMETER: f64 = HEX_SIZE * 1.9;
 
 /// Contains axial coordinates in a q/r form.
-#[derive(Clone, Copy, Debug, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Coordinates {
     pub q: i32,
     pub r: i32,
 }
 
-impl PartialEq for Coordinates {
-    fn eq(&self, other: &Self) -> bool {
-        return self.q == other.q && self.r == other.r;
-    }
-}
-
-/// COS(PI / 3)
-const M11: f64 = 0.5;
-/// COS(0)
-const M12: f64 = 1.0;
-/// SIN(PI / 3)
-const M21: f64 = 0.86602540378;
-/// SIN(0)
-const M22: f64 = 0.0;
-
-impl Into<Point> for Coordinates {
-    /// Calculate the center point in pixel.
-    fn into(self) -> Point {
-        let r = self.r as f64;
-        let q = self.q as f64;
-        let x = (HEX_DIAMETER * (M11 * r + M12 * q)) as i32;
-        let y = (HEX_DIAMETER * (M21 * r + M22 * q)) as i32;
-        Point::new(x + ((WIDTH / 4) as i32), y + ((HEIGHT / 5) as i32))
-    }
-}
-
-const DET_M: f64 = M11 * M22 - M12 * M21;
-/// constant so we know it's non null.
-
-const N11: f64 = (1.0 / DET_M) * M22;
-const N12: f64 = (1.0 / DET_M) * -M12;
-const N21: f64 = (1.0 / DET_M) * -M21;
-const N22: f64 = (1.0 / DET_M) * M11;
-
-impl From<Point> for Coordinates {
-    /// Compute [Coordinates] from pixel position.
-    fn from(point: Point) -> Self {
-        let x = f64::from(point.x) - f64::from(WIDTH) / 4.0;
-        let y = f64::from(point.y) - f64::from(HEIGHT) / 5.0;
-        let coords: FloatCoordinates = FloatCoordinates {
-            q: (1.0 / HEX_DIAMETER) * (N21 * x + N22 * y),
-            r: (1.0 / HEX_DIAMETER) * (N11 * x + N12 * y),
-        };
-        Coordinates::round(coords)
-    }
-}
-
 impl std::fmt::Display for Coordinates {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("q: {}, r: {}", self.q, self.r))
@@ -133,63 +84,70 @@ pub struct FloatCoordinates {
     pub r: f64,
 }
 
-/// Very small coordinate used to nudge a line in a direction or another.
-const COORDINATES_EPSILON: FloatCoordinates = FloatCoordinates { q: 1e-6, r: 2e-6 };
-
 impl std::fmt::Display for FloatCoordinates {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("q: {:.2}, r: {:.2}", self.q, self.r))
     }
 }
 
-impl From<Point> for FloatCoordinates {
-    /// Compute [Coordinates] from pixel position.
-    fn from(point: Point) -> Self {
-        let x = f64::from(point.x) - f64::from(WIDTH) / 4.0;
-        let y = f64::from(point.y) - f64::from(HEIGHT) / 5.0;
-        FloatCoordinates {
-            q: (1.0 / HEX_DIAMETER) * (N21 * x + N22 * y),
-            r: (1.0 / HEX_DIAMETER) * (N11 * x + N12 * y),
-        }
-    }
-}
-
 impl FloatCoordinates {
-    /// Manhattan distance between two hexes.
+    /// Number of steps between two points, see [Coordinates::distance].
     /// Coordinates don't have to exist on the board.
     pub fn distance(self, target: FloatCoordinates) -> f64 {
         let vec: FloatCoordinates = self - target;
-        let manhattan = vec.q.abs() + (vec.q.abs() + vec.r.abs()) + vec.r.abs();
-        manhattan / 2.0
+        vec.q.abs().max(vec.r.abs()).max((vec.q + vec.r).abs())
     }
 
-    pub fn get_corner(self, corner: i8) -> FloatCoordinates {
-        match corner {
-            0 => Self {
-                q: self.q + 0.3,
-                r: self.r - 0.5,
-            },
-            1 => Self {
-                q: self.q + 0.3,
-                r: self.r - 0.25,
-            },
-            2 => Self {
-                q: self.q + 0.3,
-                r: self.r + 0.3,
-            },
-            3 => Self {
-                q: self.q - 0.25,
-                r: self.r + 0.6,
-            },
-            4 => Self {
-                q: self.q - 0.6,
-                r: self.r + 0.3,
-            },
-            5 => Self {
-                q: self.q - 0.3,
-                r: self.r - 0.25,
-            },
-            _ => unreachable!(),
+    /// Corner `i` of the hex centered here, clockwise from the top right one like
+    /// [Layout::corners][super::layout::Layout::corners] with pointy hexes.
+    pub fn corner(self, i: usize) -> FloatCoordinates {
+        const THIRD: f64 = 1.0 / 3.0;
+        const CORNERS: [(f64, f64); 6] = [
+            (2.0 * THIRD, -THIRD),
+            (THIRD, THIRD),
+            (-THIRD, 2.0 * THIRD),
+            (-2.0 * THIRD, THIRD),
+            (-THIRD, -THIRD),
+            (THIRD, -2.0 * THIRD),
+        ];
+        let (q, r) = CORNERS[i];
+        Self {
+            q: self.q + q,
+            r: self.r + r,
+        }
+    }
+
+    /// Every tile and side the segment to `target` touches, ordered from here. A side comes
+    /// before the tile it leads to.
+    pub fn crossings(self, target: FloatCoordinates) -> Vec<Crossing> {
+        let candidates = line_candidates(Coordinates::round(self), Coordinates::round(target));
+        let tiles = segment_tiles(&candidates, self, target);
+        let mut crossings = tile_sides(&tiles);
+        crossings.extend(
+            tiles
+                .into_iter()
+                .map(|(coords, enter, exit)| Crossing::Tile {
+                    coords,
+                    enter,
+                    exit,
+                }),
+        );
+        crossings.sort_by(|a, b| {
+            let side_first = |crossing: &Crossing| matches!(crossing, Crossing::Tile { .. });
+            a.position()
+                .total_cmp(&b.position())
+                .then(side_first(a).cmp(&side_first(b)))
+        });
+        crossings
+    }
+}
+
+impl From<Coordinates> for FloatCoordinates {
+    /// Center of the hex.
+    fn from(coords: Coordinates) -> Self {
+        FloatCoordinates {
+            q: coords.q as f64,
+            r: coords.r as f64,
         }
     }
 }
@@ -249,6 +207,22 @@ impl Coordinates {
         Coordinates { q, r }
     }
 
+    /// Transforms cube coordinates back into x/y coordinates, see [Coordinates::from_offset].
+    pub fn to_offset(self) -> (i32, i32) {
+        let x = self.q + (self.r - (self.r & 1)) / 2;
+        (x, self.r)
+    }
+
+    /// Rotate by 60° clockwise around the origin.
+    pub fn rotate_clockwise(self) -> Self {
+        Cube::from(self).rotate_clockwise().into()
+    }
+
+    /// Rotate by 60° counterclockwise around the origin.
+    pub fn rotate_counterclockwise(self) -> Self {
+        Cube::from(self).rotate_counterclockwise().into()
+    }
+
     /// More precise coordinate round.
     pub fn round(coords: FloatCoordinates) -> Self {
         let qgrid = coords.q.round();
@@ -269,88 +243,385 @@ impl Coordinates {
         }
     }
 
-    /// Manhattan distance between two hexes.
+    /// Number of steps between two hexes.
     /// Coordinates don't have to exist on the board.
     pub fn distance(self, target: Coordinates) -> u32 {
-        let vec: Coordinates = self - target;
-        let manhattan = vec.q.abs() + (vec.q.abs() + vec.r.abs()) + vec.r.abs();
-        (manhattan / 2) as u32
+        Cube::from(self).distance(Cube::from(target))
     }
 
-    /// Returns a line. Will try to draw one using +epsilon, and one using -epsilon.
-    /// Which is returned will be determined by
-    pub fn line(self, target: Coordinates) -> (Vec<Coordinates>, Vec<Coordinates>) {
-        let mut distance = self.distance(target);
-        if distance == 0 {
-            distance = 1;
+    /// Corner `i` of the tile, see [FloatCoordinates::corner], pulled in a little toward the
+    /// center. Sight lines start and end there, see [Coordinates::strict_line_crossing].
+    pub fn sight_corner(self, i: usize) -> FloatCoordinates {
+        let center = FloatCoordinates::from(self);
+        let corner = center.corner(i) - center;
+        FloatCoordinates {
+            q: center.q + corner.q * (1.0 - CORNER_INSET),
+            r: center.r + corner.r * (1.0 - CORNER_INSET),
         }
+    }
 
-        let start_float = FloatCoordinates {
-            q: self.q as f64,
-            r: self.r as f64,
-        };
-        let end_float = FloatCoordinates {
-            q: target.q as f64,
-            r: target.r as f64,
-        };
+    /// Both corners of the side of the tile facing `direction`, like
+    /// [Layout::side_corners][super::layout::Layout::side_corners].
+    pub fn side(self, direction: Direction) -> (FloatCoordinates, FloatCoordinates) {
+        let index: i32 = direction.into();
+        let center = FloatCoordinates::from(self);
+        (
+            center.corner(((index + 5) % 6) as usize),
+            center.corner(index as usize),
+        )
+    }
 
-        let mut first: Vec<Coordinates> = Vec::with_capacity(distance as usize);
-        let mut second: Vec<Coordinates> = Vec::with_capacity(distance as usize);
+    /// Check if line of sight can be achieved using argument `check` as constraint.
+    ///
+    /// The line goes from the ground of this tile to the ground of the target. Tiles it passes
+    /// under block it, and tiles it passes over, like walls below a ledge, are not checked.
+    /// Sight goes both ways: swapping both ends gives the same answer.
+    pub fn strict_line<F>(self, board: &Board, target: Coordinates, check: F) -> bool
+    where
+        F: Fn(Option<&Tile>) -> bool,
+    {
+        self.strict_line_over(board, target, |_, tile| check(tile))
+    }
 
-        for i in 1..(distance) {
-            let plus = axial_lerp(
-                start_float + COORDINATES_EPSILON,
-                end_float,
-                1.0 / distance as f64 * i as f64,
-            );
-            let minus = axial_lerp(
-                start_float - COORDINATES_EPSILON,
-                end_float,
-                1.0 / distance as f64 * i as f64,
-            );
-            first.push(Coordinates::round(plus));
-            second.push(Coordinates::round(minus))
+    /// Like [Coordinates::strict_line], but `check` is also given the coordinates of the tile.
+    pub fn strict_line_over<F>(self, board: &Board, target: Coordinates, check: F) -> bool
+    where
+        F: Fn(Coordinates, Option<&Tile>) -> bool,
+    {
+        // Always look from the same end, so heights are compared the same way both ways.
+        if (target.r, target.q) < (self.r, self.q) {
+            return target.strict_line_over(board, self, check);
         }
+        self.strict_line_crossing(target, self.over(board, target, check))
+    }
 
-        (first, second)
+    /// Every [sight line][Coordinates::sight_lines] of [Coordinates::strict_line_over].
+    pub fn sight_lines_over<F>(
+        self,
+        board: &Board,
+        target: Coordinates,
+        check: F,
+    ) -> Vec<Vec<Crossing>>
+    where
+        F: Fn(Coordinates, Option<&Tile>) -> bool,
+    {
+        if (target.r, target.q) < (self.r, self.q) {
+            return target.sight_lines_over(board, self, check);
+        }
+        self.sight_lines(target, self.over(board, target, check))
     }
 
-    /// Check if line of sight can be achieved using argument `check` as constraint.
-    pub fn strict_line<F>(self, board: &Board, target: Coordinates, check: F) -> bool
+    /// Check of a [Crossing] for [Coordinates::strict_line_over], with heights and edges.
+    fn over<'a, F>(
+        self,
+        board: &'a Board,
+        target: Coordinates,
+        check: F,
+    ) -> impl Fn(Crossing) -> bool + 'a
     where
-        F: Fn(Option<&Tile>) -> bool,
+        F: Fn(Coordinates, Option<&Tile>) -> bool + 'a,
     {
-        let start_float = FloatCoordinates {
-            q: self.q as f64,
-            r: self.r as f64,
-        };
-        let end_float = FloatCoordinates {
-            q: target.q as f64,
-            r: target.r as f64,
+        let height = |coords| board.get(coords).map_or(0, |tile| tile.height) as f64;
+        let (from_height, to_height) = (height(self), height(target));
+        let eye = move |t: f64| from_height + (to_height - from_height) * t;
+
+        move |crossing| match crossing {
+            Crossing::Side { from, to, .. } => !board
+                .edge_between(from, to)
+                .is_some_and(EdgeKind::blocks_sight),
+            Crossing::Tile {
+                coords,
+                enter,
+                exit,
+            } => {
+                let tile = board.get(coords);
+                if let Some(tile) = tile.filter(|_| coords != self && coords != target) {
+                    let lowest = eye(enter).min(eye(exit));
+                    if tile.height as f64 > lowest {
+                        return false;
+                    }
+                    if (tile.top() as f64) < lowest {
+                        return true;
+                    }
+                }
+                check(coords, tile)
+            }
+        }
+    }
+
+    /// Like [Coordinates::strict_line], but `check` is given the coordinates of each crossed tile.
+    /// Heights and edges are ignored.
+    pub fn strict_line_by<F>(self, target: Coordinates, check: F) -> bool
+    where
+        F: Fn(Coordinates) -> bool,
+    {
+        self.strict_line_crossing(target, |crossing| match crossing {
+            Crossing::Tile { coords, .. } => check(coords),
+            Crossing::Side { .. } => true,
+        })
+    }
+
+    /// Like [Coordinates::strict_line_by], but `check` is given every [Crossing] of the line:
+    /// the tiles in order, both ends included, then the sides between them in order.
+    ///
+    /// Lines go from a corner of this tile to a corner of the target, and sight is clear if
+    /// `check` accepts everything along at least one of them. Corners are pulled in a little
+    /// so lines don't start on the neighbours, and tiles or sides merely touched by a line
+    /// count as crossed.
+    pub fn strict_line_crossing<F>(self, target: Coordinates, check: F) -> bool
+    where
+        F: Fn(Crossing) -> bool,
+    {
+        self.corner_lines(target).any(|tiles| {
+            tiles
+                .iter()
+                .map(|(coords, enter, exit)| Crossing::Tile {
+                    coords: *coords,
+                    enter: *enter,
+                    exit: *exit,
+                })
+                .all(&check)
+                && tile_sides(&tiles).into_iter().all(&check)
+        })
+    }
+
+    /// Every crossing `check` rejects, for each of the 36 lines of
+    /// [Coordinates::strict_line_crossing]. Lines with none are clear.
+    pub fn sight_lines<F>(self, target: Coordinates, check: F) -> Vec<Vec<Crossing>>
+    where
+        F: Fn(Crossing) -> bool,
+    {
+        self.corner_lines(target)
+            .map(|tiles| {
+                let mut crossings = tile_sides(&tiles);
+                crossings.extend(tiles.iter().map(|(coords, enter, exit)| Crossing::Tile {
+                    coords: *coords,
+                    enter: *enter,
+                    exit: *exit,
+                }));
+                crossings.retain(|crossing| !check(*crossing));
+                crossings.sort_by(|a, b| a.position().total_cmp(&b.position()));
+                crossings
+            })
+            .collect()
+    }
+
+    /// Tiles touched by each line from a corner of this tile to a corner of the target, see
+    /// [segment_tiles], ordered from this tile.
+    fn corner_lines(
+        self,
+        target: Coordinates,
+    ) -> impl Iterator<Item = Vec<(Coordinates, f64, f64)>> {
+        // Both ends are swapped into the same order, so the maths are the same both ways.
+        let swapped = (target.r, target.q) < (self.r, self.q);
+        let (start, end) = if swapped {
+            (target, self)
+        } else {
+            (self, target)
         };
+        let candidates = line_candidates(start, end);
 
-        for start_angle in 0..6 {
-            let start_float = start_float.get_corner(start_angle);
-            'point: for end_angle in 0..6 {
-                let end_float = end_float.get_corner(end_angle);
-
-                let actual_distance = start_float.distance(end_float);
-
-                for i in 1..((actual_distance * 16.0).floor() as i32) {
-                    let tile_minus = board.get(Coordinates::round(axial_lerp(
-                        start_float,
-                        end_float,
-                        1.0 / actual_distance * (i as f64 / 16.0),
-                    )));
-                    if !check(tile_minus) {
-                        continue 'point;
-                    }
+        (0..36).map(move |i| {
+            let mut tiles = segment_tiles(
+                &candidates,
+                start.sight_corner(i / 6),
+                end.sight_corner(i % 6),
+            );
+            if swapped {
+                tiles.reverse();
+                for (_, enter, exit) in tiles.iter_mut() {
+                    (*enter, *exit) = (1.0 - *exit, 1.0 - *enter);
                 }
+            }
+            tiles
+        })
+    }
+}
+
+/// How far sight lines start and end from the very corners, see
+/// [Coordinates::strict_line_crossing]. Relative to the distance from the center.
+const CORNER_INSET: f64 = 1e-3;
+/// Rounding error allowed when checking if a point is on a tile.
+const GEOMETRY_EPSILON: f64 = 1e-9;
+
+/// Something a sight line goes through, see [Coordinates::strict_line_crossing].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Crossing {
+    /// Through a tile, from `enter` to `exit`, as fractions of the line from its start.
+    Tile {
+        coords: Coordinates,
+        enter: f64,
+        exit: f64,
+    },
+    /// Across the side between two neighbouring tiles, going from `from` to `to`, at `at`.
+    Side {
+        from: Coordinates,
+        to: Coordinates,
+        at: f64,
+    },
+}
 
-                return true;
+impl Crossing {
+    /// Where along the line it happens, as a fraction from its start.
+    fn position(&self) -> f64 {
+        match self {
+            Self::Tile { enter, .. } => *enter,
+            Self::Side { at, .. } => *at,
+        }
+    }
+}
+
+/// Part of the segment from `a` to `b` lying on the closed hex `coords`, as fractions of the
+/// segment. None if it doesn't touch the hex.
+fn segment_on_hex(
+    a: FloatCoordinates,
+    b: FloatCoordinates,
+    coords: Coordinates,
+) -> Option<(f64, f64)> {
+    // A point is on a hex if its cube coordinates, relative to the hex, differ by at most 1
+    // on each pair of axes. Along the segment, those differences are linear.
+    let (dq, dr) = (a.q - coords.q as f64, a.r - coords.r as f64);
+    let (vq, vr) = (b.q - a.q, b.r - a.r);
+    let (mut enter, mut exit) = (0.0_f64, 1.0_f64);
+    for (start, slope) in [
+        (dq - dr, vq - vr),
+        (dq + 2.0 * dr, vq + 2.0 * vr),
+        (-2.0 * dq - dr, -2.0 * vq - vr),
+    ] {
+        let bound = 1.0 + GEOMETRY_EPSILON;
+        if slope.abs() < GEOMETRY_EPSILON {
+            if start.abs() > bound {
+                return None;
+            }
+            continue;
+        }
+        let (t0, t1) = ((-bound - start) / slope, (bound - start) / slope);
+        enter = enter.max(t0.min(t1));
+        exit = exit.min(t0.max(t1));
+        if enter > exit {
+            return None;
+        }
+    }
+    Some((enter, exit))
+}
+
+/// Every tile that lines from `from` to `to` may cross, see [segment_crossings].
+fn line_candidates(from: Coordinates, to: Coordinates) -> Vec<Coordinates> {
+    // Any point between both tiles is a point of the line between their centers, plus a point
+    // of a tile. Sampling that line every quarter step, such points are at most two tiles away
+    // from the tile of the closest sample.
+    let (a, b) = (FloatCoordinates::from(from), FloatCoordinates::from(to));
+    let samples = from.distance(to).max(1) * 4;
+    let mut candidates: Vec<Coordinates> = (0..=samples)
+        .flat_map(|i| Coordinates::round(axial_lerp(a, b, i as f64 / samples as f64)).range(2))
+        .collect();
+    candidates.sort_by_key(|coords| (coords.r, coords.q));
+    candidates.dedup();
+    candidates
+}
+
+/// Every tile touched by the segment from `a` to `b`, with where it enters and exits it, ordered
+/// from the start. Only `candidates` are checked.
+fn segment_tiles(
+    candidates: &[Coordinates],
+    a: FloatCoordinates,
+    b: FloatCoordinates,
+) -> Vec<(Coordinates, f64, f64)> {
+    let mut tiles: Vec<(Coordinates, f64, f64)> = candidates
+        .iter()
+        .filter_map(|&coords| {
+            let (enter, exit) = segment_on_hex(a, b, coords)?;
+            Some((coords, enter, exit))
+        })
+        .collect();
+    tiles.sort_by(|(_, a, _), (_, b, _)| a.total_cmp(b));
+    tiles
+}
+
+/// Every side between two of the tiles a segment touches, see [segment_tiles], that the
+/// segment touches too, ordered from the start.
+fn tile_sides(tiles: &[(Coordinates, f64, f64)]) -> Vec<Crossing> {
+    // Two neighbouring hexes only share their side, so the segment touches the side exactly
+    // where it is on both hexes.
+    let mut sides: Vec<Crossing> = vec![];
+    for (i, (from, from_enter, from_exit)) in tiles.iter().enumerate() {
+        for (to, to_enter, to_exit) in tiles[i + 1..].iter() {
+            let at = from_enter.max(*to_enter);
+            if from.distance(*to) == 1 && at <= from_exit.min(*to_exit) + GEOMETRY_EPSILON {
+                sides.push(Crossing::Side {
+                    from: *from,
+                    to: *to,
+                    at,
+                });
             }
         }
+    }
+    sides.sort_by(|a, b| a.position().total_cmp(&b.position()));
+    sides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::board::BoardConfig;
+    use crate::board::outline::Outline;
+
+    fn sees(board: &Board, from: Coordinates, to: Coordinates) -> bool {
+        board.line_of_sight(from, to, |_| false)
+    }
+
+    #[test]
+    fn exact_corners() {
+        let center = FloatCoordinates { q: 2.0, r: -1.0 };
+        for i in 0..6 {
+            let corner = center.corner(i);
+            // Every corner is shared by three hexes, two thirds of a step away from each.
+            assert!((corner.distance(center) - 2.0 / 3.0).abs() < 1e-12);
+            assert!((corner.distance(center.corner((i + 1) % 6)) - 2.0 / 3.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn symmetric() {
+        let mut board = Board::from_seed(
+            3,
+            &BoardConfig {
+                outline: Outline::Hexagon { radius: 4 },
+                obstacle_ratio: 0.3,
+            },
+        );
+        let coords = board.sorted_coordinates();
+        for (i, coords) in coords.iter().enumerate().step_by(5) {
+            board.set_height(*coords, (i % 3) as i32 - 1);
+        }
+        for a in coords.iter() {
+            for b in coords.iter() {
+                assert_eq!(sees(&board, *a, *b), sees(&board, *b, *a), "{} / {}", a, b);
+            }
+        }
+    }
 
-        false
+    #[test]
+    fn grazing_corners() {
+        let mut board = Board::from_outline(&Outline::Hexagon { radius: 3 });
+        let from = Coordinates::from_offset(3, 3);
+        let diagonal = from.diagonal(Direction::TopRight);
+        let (left, right) = (from + Direction::TopRight, from + Direction::Right);
+
+        // Two walls meeting at a corner seal the diagonal between them.
+        board.fill(left);
+        assert!(sees(&board, from, diagonal));
+        board.fill(right);
+        assert!(!sees(&board, from, diagonal));
+        assert!(!sees(&board, diagonal, from));
+        board.free(left);
+        assert!(sees(&board, diagonal, from));
+
+        // A line running right along the side of a wall touches it.
+        board.free(right);
+        let row = from + Direction::Right + Direction::Right;
+        board.fill(from + Direction::Right);
+        assert!(!sees(&board, from, row));
+        assert!(!sees(&board, row, from));
     }
 }
@@ -0,0 +1,118 @@
+use super::board::Board;
+use super::coordinates::{Coordinates, Crossing};
+use super::edge::Edge;
+use super::occupant::Occupant;
+
+/// Number of sight lines between two tiles, one from each corner to each corner.
+pub const SIGHT_LINES: usize = 36;
+
+/// How well a shot gets through, see [Board::cover].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CoverLevel {
+    /// Every sight line is clear.
+    Clear,
+    /// Some sight lines are clear, some are blocked.
+    Partial,
+    /// No sight line is clear: there is no [line of sight][Board::line_of_sight].
+    Blocked,
+}
+
+/// Cover between a shooter and a target, and what gives it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cover {
+    pub level: CoverLevel,
+    /// Sight lines left clear, out of [SIGHT_LINES].
+    pub clear_lines: usize,
+    /// Tiles blocking at least one sight line, closest to the shooter first.
+    pub tiles: Vec<Coordinates>,
+    /// Walls and closed doors blocking at least one sight line.
+    pub edges: Vec<Edge>,
+}
+
+impl Board {
+    /// Cover between two tiles, graded by how many of the sight lines of
+    /// [Board::line_of_sight] are blocked, with the same rules.
+    pub fn cover<F>(&self, from: Coordinates, to: Coordinates, blocks: F) -> Cover
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        let lines = from.sight_lines_over(self, to, self.sight_check(from, to, blocks));
+
+        let clear_lines = lines.iter().filter(|line| line.is_empty()).count();
+        let mut tiles: Vec<Coordinates> = vec![];
+        let mut edges: Vec<Edge> = vec![];
+        for crossing in lines.into_iter().flatten() {
+            match crossing {
+                Crossing::Tile { coords, .. } => tiles.push(coords),
+                Crossing::Side { from, to, .. } => edges.extend(Edge::between(from, to)),
+            }
+        }
+        tiles.sort_by_key(|coords| (from.distance(*coords), coords.r, coords.q));
+        tiles.dedup();
+        edges.sort_by_key(|edge| {
+            let (a, b) = edge.tiles();
+            (from.distance(a).min(from.distance(b)), a.r, a.q, b.r, b.q)
+        });
+        edges.dedup();
+
+        let level = match clear_lines {
+            0 => CoverLevel::Blocked,
+            SIGHT_LINES => CoverLevel::Clear,
+            _ => CoverLevel::Partial,
+        };
+        Cover {
+            level,
+            clear_lines,
+            tiles,
+            edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::direction::Direction;
+    use crate::board::edge::EdgeKind;
+    use crate::board::outline::Outline;
+    use crate::board::tile::TileKind;
+
+    #[test]
+    fn graded() {
+        let tiles = Coordinates { q: 0, r: 0 }.range(4).collect();
+        let mut board = Board::from_outline(&Outline::Mask(tiles));
+        let from = Coordinates { q: -3, r: 0 };
+        let to = Coordinates { q: 3, r: 0 };
+        let clear = board.cover(from, to, |_| false);
+        assert_eq!(clear.level, CoverLevel::Clear);
+        assert_eq!(clear.clear_lines, SIGHT_LINES);
+        assert!(clear.tiles.is_empty() && clear.edges.is_empty());
+
+        // A rock just off the line only stops some sight lines.
+        let rock = Coordinates { q: 0, r: -1 };
+        board.set(rock, TileKind::Rock);
+        let partial = board.cover(from, to, |_| false);
+        assert_eq!(partial.level, CoverLevel::Partial);
+        assert!(partial.clear_lines < SIGHT_LINES);
+        assert_eq!(partial.tiles, vec![rock]);
+        assert!(board.line_of_sight(from, to, |_| false));
+
+        // A rock right on the line stops them all.
+        let middle = Coordinates { q: 0, r: 0 };
+        board.set(middle, TileKind::Rock);
+        let blocked = board.cover(from, to, |_| false);
+        assert_eq!(blocked.level, CoverLevel::Blocked);
+        assert_eq!(blocked.tiles, vec![rock, middle]);
+        assert!(!board.line_of_sight(from, to, |_| false));
+        assert_eq!(board.cover(to, from, |_| false).level, CoverLevel::Blocked);
+
+        // Walls count too.
+        board.set(rock, TileKind::Floor);
+        board.set(middle, TileKind::Floor);
+        board.set_edge(middle, Direction::TopRight, Some(EdgeKind::Wall));
+        let walled = board.cover(from, to, |_| false);
+        assert_eq!(walled.level, CoverLevel::Partial);
+        assert!(walled.tiles.is_empty());
+        assert_eq!(walled.edges, vec![Edge::new(middle, Direction::TopRight)]);
+    }
+}
@@ -0,0 +1,315 @@
+use super::coordinates::Coordinates;
+use super::direction::Direction;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Cube coordinates: axial [Coordinates] plus the third axis, so that `q + r + s == 0`.
+///
+/// Distances, rotations and ranges are symmetric over the three axes, which makes them much
+/// simpler to write in cube form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cube {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+impl Cube {
+    /// Cube from its first two axes.
+    pub fn new(q: i32, r: i32) -> Self {
+        Cube { q, r, s: -q - r }
+    }
+
+    /// Number of steps to the origin.
+    pub fn length(self) -> u32 {
+        self.q
+            .unsigned_abs()
+            .max(self.r.unsigned_abs())
+            .max(self.s.unsigned_abs())
+    }
+
+    /// Number of steps between two hexes.
+    pub fn distance(self, target: Cube) -> u32 {
+        (self - target).length()
+    }
+
+    /// Rotate by 60° clockwise around the origin.
+    pub fn rotate_clockwise(self) -> Self {
+        Cube {
+            q: -self.r,
+            r: -self.s,
+            s: -self.q,
+        }
+    }
+
+    /// Rotate by 60° counterclockwise around the origin.
+    pub fn rotate_counterclockwise(self) -> Self {
+        Cube {
+            q: -self.s,
+            r: -self.q,
+            s: -self.r,
+        }
+    }
+}
+
+impl From<Coordinates> for Cube {
+    fn from(coords: Coordinates) -> Self {
+        Cube::new(coords.q, coords.r)
+    }
+}
+
+impl From<Cube> for Coordinates {
+    fn from(cube: Cube) -> Self {
+        Coordinates {
+            q: cube.q,
+            r: cube.r,
+        }
+    }
+}
+
+impl From<Direction> for Cube {
+    /// Single step in that direction.
+    fn from(direction: Direction) -> Self {
+        Cube::from(Coordinates { q: 0, r: 0 } + direction)
+    }
+}
+
+impl Add<Cube> for Cube {
+    type Output = Cube;
+
+    fn add(self, rhs: Cube) -> Self::Output {
+        Cube {
+            q: self.q + rhs.q,
+            r: self.r + rhs.r,
+            s: self.s + rhs.s,
+        }
+    }
+}
+
+impl Sub<Cube> for Cube {
+    type Output = Cube;
+
+    fn sub(self, rhs: Cube) -> Self::Output {
+        Cube {
+            q: self.q - rhs.q,
+            r: self.r - rhs.r,
+            s: self.s - rhs.s,
+        }
+    }
+}
+
+impl Mul<i32> for Cube {
+    type Output = Cube;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Cube {
+            q: self.q * rhs,
+            r: self.r * rhs,
+            s: self.s * rhs,
+        }
+    }
+}
+
+impl Neg for Cube {
+    type Output = Cube;
+
+    fn neg(self) -> Self::Output {
+        self * -1
+    }
+}
+
+impl Coordinates {
+    /// Neighbouring hex in that direction. Coordinates don't have to exist on the board.
+    pub fn neighbour(self, direction: Direction) -> Self {
+        self + direction
+    }
+
+    /// Every neighbouring hex, clockwise from [TopRight][Direction::TopRight].
+    pub fn neighbours(self) -> impl Iterator<Item = Coordinates> {
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| self + direction)
+    }
+
+    /// Hex two steps away, past the corner between `direction` and the next direction
+    /// clockwise.
+    pub fn diagonal(self, direction: Direction) -> Self {
+        let index: i32 = direction.into();
+        self + direction + Direction::from(index + 1)
+    }
+
+    /// Every diagonal hex, see [Coordinates::diagonal], clockwise from the one between
+    /// [TopRight][Direction::TopRight] and [Right][Direction::Right].
+    pub fn diagonals(self) -> impl Iterator<Item = Coordinates> {
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| self.diagonal(direction))
+    }
+
+    /// Every hex exactly `radius` steps away, clockwise from the one `radius` steps
+    /// [Left][Direction::Left]. A radius of 0 is just this hex.
+    pub fn ring(self, radius: u32) -> impl Iterator<Item = Coordinates> {
+        let center = Cube::from(self);
+        let sides = if radius == 0 { 1 } else { 6 };
+        (0..sides).flat_map(move |side| {
+            // Each side starts at a corner and stops before the next one.
+            let corner = center + Cube::from(Direction::ALL[(side + 4) % 6]) * radius as i32;
+            let step = Cube::from(Direction::ALL[side]);
+            (0..radius.max(1)).map(move |i| Coordinates::from(corner + step * i as i32))
+        })
+    }
+
+    /// Every hex at most `radius` steps away, ring after ring, starting with this hex.
+    pub fn spiral(self, radius: u32) -> impl Iterator<Item = Coordinates> {
+        (0..=radius).flat_map(move |ring| self.ring(ring))
+    }
+
+    /// Every hex at most `n` steps away, row by row.
+    pub fn range(self, n: u32) -> impl Iterator<Item = Coordinates> {
+        let n = n as i32;
+        (-n..=n).flat_map(move |r| {
+            let from = (-n).max(-r - n);
+            let to = n.min(-r + n);
+            (from..=to).map(move |q| self + Coordinates { q, r })
+        })
+    }
+
+    /// Every hex at most `n` steps away from this hex, and at most `m` steps away from
+    /// `other`, row by row.
+    pub fn range_intersection(
+        self,
+        n: u32,
+        other: Coordinates,
+        m: u32,
+    ) -> impl Iterator<Item = Coordinates> {
+        let (a, b) = (Cube::from(self), Cube::from(other));
+        let (n, m) = (n as i32, m as i32);
+        let (q_min, q_max) = ((a.q - n).max(b.q - m), (a.q + n).min(b.q + m));
+        let (r_min, r_max) = ((a.r - n).max(b.r - m), (a.r + n).min(b.r + m));
+        let (s_min, s_max) = ((a.s - n).max(b.s - m), (a.s + n).min(b.s + m));
+        (r_min..=r_max).flat_map(move |r| {
+            let from = q_min.max(-r - s_max);
+            let to = q_max.min(-r - s_min);
+            (from..=to).map(move |q| Coordinates { q, r })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const CENTER: Coordinates = Coordinates { q: 2, r: -3 };
+
+    #[test]
+    fn cube() {
+        let cube = Cube::from(Coordinates { q: 3, r: -1 });
+        assert_eq!(cube, Cube { q: 3, r: -1, s: -2 });
+        assert_eq!(Coordinates::from(cube), Coordinates { q: 3, r: -1 });
+        assert_eq!(cube.length(), 3);
+        assert_eq!(-cube + cube, Cube::new(0, 0));
+        for direction in Direction::ALL {
+            let step = Cube::from(direction);
+            assert_eq!(step.q + step.r + step.s, 0);
+            assert_eq!(step.length(), 1);
+        }
+
+        // Six rotations in either direction go full circle.
+        let mut rotated = cube;
+        for _ in 0..6 {
+            rotated = rotated.rotate_clockwise();
+            assert_eq!(rotated.length(), cube.length());
+        }
+        assert_eq!(rotated, cube);
+        assert_eq!(cube.rotate_clockwise().rotate_counterclockwise(), cube);
+    }
+
+    #[test]
+    fn distance() {
+        let origin = Coordinates { q: 0, r: 0 };
+        assert_eq!(origin.distance(origin), 0);
+        assert_eq!(origin.distance(Coordinates { q: 1, r: -1 }), 1);
+        assert_eq!(origin.distance(Coordinates { q: 3, r: -3 }), 3);
+        assert_eq!(origin.distance(Coordinates { q: 2, r: 1 }), 3);
+        assert_eq!(origin.distance(Coordinates { q: -2, r: -2 }), 4);
+        assert_eq!(
+            CENTER.distance(Coordinates { q: 5, r: -1 }),
+            Coordinates { q: 5, r: -1 }.distance(CENTER)
+        );
+    }
+
+    #[test]
+    fn neighbours_and_diagonals() {
+        let neighbours: Vec<Coordinates> = CENTER.neighbours().collect();
+        assert_eq!(neighbours.len(), 6);
+        assert_eq!(neighbours[1], CENTER.neighbour(Direction::Right));
+        assert!(neighbours
+            .iter()
+            .all(|coords| CENTER.distance(*coords) == 1));
+
+        let diagonals: Vec<Coordinates> = CENTER.diagonals().collect();
+        assert_eq!(diagonals[0], CENTER + Coordinates { q: 2, r: -1 });
+        assert_eq!(diagonals[3], CENTER + Coordinates { q: -2, r: 1 });
+        for diagonal in diagonals.iter() {
+            assert_eq!(CENTER.distance(*diagonal), 2);
+            // Both hexes share exactly two neighbours.
+            let shared = diagonal
+                .neighbours()
+                .filter(|coords| neighbours.contains(coords))
+                .count();
+            assert_eq!(shared, 2);
+        }
+    }
+
+    #[test]
+    fn rings_and_spirals() {
+        assert_eq!(CENTER.ring(0).collect::<Vec<_>>(), vec![CENTER]);
+        for radius in 1..5 {
+            let ring: Vec<Coordinates> = CENTER.ring(radius).collect();
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert_eq!(
+                ring[0],
+                CENTER
+                    + Coordinates {
+                        q: -(radius as i32),
+                        r: 0
+                    }
+            );
+            assert!(ring.iter().all(|coords| CENTER.distance(*coords) == radius));
+            // Every hex follows the one before, all the way around.
+            for (i, coords) in ring.iter().enumerate() {
+                assert_eq!(coords.distance(ring[(i + 1) % ring.len()]), 1);
+            }
+            assert_eq!(ring.iter().collect::<HashSet<_>>().len(), ring.len());
+        }
+
+        let spiral: Vec<Coordinates> = CENTER.spiral(3).collect();
+        assert_eq!(spiral.len(), 37);
+        assert_eq!(spiral[0], CENTER);
+        assert!(spiral
+            .windows(2)
+            .all(|pair| CENTER.distance(pair[0]) <= CENTER.distance(pair[1])));
+    }
+
+    #[test]
+    fn ranges() {
+        for n in 0..5 {
+            let range: HashSet<Coordinates> = CENTER.range(n).collect();
+            assert_eq!(range.len(), (3 * n * (n + 1) + 1) as usize);
+            assert_eq!(range, CENTER.spiral(n).collect());
+        }
+
+        let other = CENTER + Coordinates { q: 3, r: -1 };
+        let both: Vec<Coordinates> = CENTER.range_intersection(3, other, 2).collect();
+        let expected: Vec<Coordinates> = CENTER
+            .range(3)
+            .filter(|coords| other.distance(*coords) <= 2)
+            .collect();
+        assert!(!both.is_empty());
+        assert_eq!(both, expected);
+
+        // Ranges too far apart don't meet.
+        let far = CENTER + Coordinates { q: 10, r: 0 };
+        assert_eq!(CENTER.range_intersection(3, far, 2).count(), 0);
+    }
+}
@@ -1,7 +1,8 @@
+use serde::{Deserialize, Serialize};
 use std::convert::{From, Into};
 
 /// Direction from one [Tile][super::tile::Tile] to the next.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Direction {
     TopRight,
     Right,
@@ -11,6 +12,24 @@ pub enum Direction {
     TopLeft,
 }
 
+impl Direction {
+    /// Every direction, clockwise from [TopRight][Direction::TopRight].
+    pub const ALL: [Direction; 6] = [
+        Self::TopRight,
+        Self::Right,
+        Self::BottomRight,
+        Self::BottomLeft,
+        Self::Left,
+        Self::TopLeft,
+    ];
+
+    /// Direction pointing the other way.
+    pub fn opposite(self) -> Self {
+        let index: i32 = self.into();
+        Self::from(index + 3)
+    }
+}
+
 impl From<i32> for Direction {
     /// Convert value%6 from [TopRight][Direction::TopRight] to [TopLeft][Direction::TopLeft] clockwise.
     fn from(value: i32) -> Self {
@@ -0,0 +1,173 @@
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::direction::Direction;
+use super::shape::Shape;
+use super::tile::TileKind;
+
+/// What came out of drilling or blasting.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DrillResult {
+    /// Every tile dug out, with what it was made of. They are all [Floor][TileKind::Floor] now.
+    pub removed: Vec<(Coordinates, TileKind)>,
+    /// Total [drill cost][TileKind::drill_cost] of the removed tiles.
+    pub cost: u32,
+    /// Tiles that were too hard for the drill.
+    pub resisted: Vec<Coordinates>,
+}
+
+impl DrillResult {
+    /// How many tiles of a kind were dug out, to count minerals and nitra collected.
+    pub fn count(&self, kind: TileKind) -> usize {
+        self.removed
+            .iter()
+            .filter(|(_, removed)| *removed == kind)
+            .count()
+    }
+
+    fn dig(&mut self, board: &mut Board, coords: Coordinates, power: u32) {
+        let kind = match board.get(coords) {
+            Some(tile) => tile.kind,
+            None => return,
+        };
+        match kind.hardness() {
+            Some(hardness) if hardness <= power => {
+                board.free(coords);
+                self.removed.push((coords, kind));
+                self.cost += kind.drill_cost();
+            }
+            Some(_) => self.resisted.push(coords),
+            None => (),
+        }
+    }
+}
+
+impl Board {
+    /// Dig out a single tile if `power` is at least its [hardness][TileKind::hardness].
+    /// Open tiles and tiles outside of the board are left alone.
+    pub fn drill(&mut self, coords: Coordinates, power: u32) -> DrillResult {
+        let mut result = DrillResult::default();
+        result.dig(self, coords, power);
+        result
+    }
+
+    /// Dig up to `length` tiles in a straight line, starting next to `from`.
+    /// Stops at the first tile that is too hard, or at the edge of the board.
+    pub fn drill_line(
+        &mut self,
+        from: Coordinates,
+        direction: Direction,
+        length: u32,
+        power: u32,
+    ) -> DrillResult {
+        let mut result = DrillResult::default();
+        let mut coords = from;
+        for _ in 0..length {
+            coords = coords + direction;
+            if self.get(coords).is_none() {
+                break;
+            }
+            result.dig(self, coords, power);
+            if !result.resisted.is_empty() {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Blast every tile of the shape at once. Tiles too hard for `power` are left standing.
+    pub fn carve(&mut self, shape: &Shape, power: u32) -> DrillResult {
+        let mut result = DrillResult::default();
+        for coords in shape.tiles.iter() {
+            result.dig(self, *coords + shape.center, power);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::event::BoardEvent;
+
+    const MAP: &str = "\
+# : $ # .
+ . * # ~
+";
+
+    #[test]
+    fn drill() {
+        let mut board: Board = MAP.parse().unwrap();
+        let dirt = Coordinates::from_offset(1, 0);
+        let mineral = Coordinates::from_offset(2, 0);
+
+        let result = board.drill(dirt, 1);
+        assert_eq!(result.removed, vec![(dirt, TileKind::Dirt)]);
+        assert_eq!(result.cost, 1);
+        assert_eq!(board.get(dirt).unwrap().kind, TileKind::Floor);
+
+        let result = board.drill(mineral, 2);
+        assert_eq!(result.removed, vec![]);
+        assert_eq!(result.resisted, vec![mineral]);
+        assert_eq!(board.get(mineral).unwrap().kind, TileKind::Mineral);
+
+        // Nothing to dig.
+        assert_eq!(board.drill(dirt, 3), DrillResult::default());
+        assert_eq!(
+            board.drill(Coordinates::from_offset(0, 1), 3),
+            DrillResult::default()
+        );
+        assert_eq!(
+            board.drill(Coordinates::from_offset(8, 8), 3),
+            DrillResult::default()
+        );
+    }
+
+    #[test]
+    fn drill_line() {
+        let mut board: Board = MAP.parse().unwrap();
+        let start = Coordinates::from_offset(0, 0);
+
+        // Dirt and floor are passed, mineral stops the drill.
+        let result = board.drill_line(start, Direction::Right, 4, 2);
+        assert_eq!(
+            result.removed,
+            vec![(start + Direction::Right, TileKind::Dirt)]
+        );
+        assert_eq!(result.resisted, vec![Coordinates::from_offset(2, 0)]);
+
+        let result = board.drill_line(start, Direction::Right, 10, 3);
+        assert_eq!(result.count(TileKind::Mineral), 1);
+        assert_eq!(result.count(TileKind::Rock), 1);
+        assert_eq!(result.cost, 3 + 2);
+        assert!(result.resisted.is_empty());
+    }
+
+    #[test]
+    fn carve() {
+        let mut board: Board = MAP.parse().unwrap();
+        let center = Coordinates::from_offset(2, 1);
+        let shape = Shape {
+            center,
+            tiles: vec![
+                Coordinates { q: 0, r: 0 },
+                Coordinates { q: 0, r: -1 },
+                Coordinates { q: 1, r: -1 },
+                Coordinates { q: -1, r: 0 },
+            ],
+        };
+
+        let result = board.carve(&shape, 2);
+        assert_eq!(result.count(TileKind::Rock), 2);
+        assert_eq!(result.count(TileKind::Nitra), 1);
+        assert_eq!(result.resisted, vec![center + Coordinates { q: 0, r: -1 }]);
+        assert_eq!(result.cost, 2 + 2 + 3);
+        assert_eq!(
+            board
+                .drain_events()
+                .iter()
+                .filter(|event| matches!(event, BoardEvent::TileChanged { .. }))
+                .count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::direction::Direction;
+use super::event::BoardEvent;
+use super::layout::Layout;
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::rect::Point;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+/// Side shared by two neighbouring tiles.
+///
+/// Both tiles name the same edge: it is always stored from the tile whose side faces
+/// [Right][Direction::Right], [BottomRight][Direction::BottomRight] or
+/// [BottomLeft][Direction::BottomLeft].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "EdgeParts")]
+pub struct Edge {
+    coords: Coordinates,
+    direction: Direction,
+}
+
+/// Saved form of an [Edge], normalized when loading.
+#[derive(Deserialize)]
+struct EdgeParts {
+    coords: Coordinates,
+    direction: Direction,
+}
+
+impl From<EdgeParts> for Edge {
+    fn from(parts: EdgeParts) -> Self {
+        Edge::new(parts.coords, parts.direction)
+    }
+}
+
+impl Edge {
+    /// Side of `coords` facing `direction`.
+    pub fn new(coords: Coordinates, direction: Direction) -> Self {
+        match direction {
+            Direction::Right | Direction::BottomRight | Direction::BottomLeft => {
+                Edge { coords, direction }
+            }
+            _ => Edge {
+                coords: coords + direction,
+                direction: direction.opposite(),
+            },
+        }
+    }
+
+    /// Edge between two tiles, if they are neighbours.
+    pub fn between(from: Coordinates, to: Coordinates) -> Option<Self> {
+        Direction::ALL
+            .iter()
+            .find(|direction| from + **direction == to)
+            .map(|direction| Edge::new(from, *direction))
+    }
+
+    /// Both tiles sharing the edge.
+    pub fn tiles(&self) -> (Coordinates, Coordinates) {
+        (self.coords, self.coords + self.direction)
+    }
+
+    pub fn coordinates(&self) -> Coordinates {
+        self.coords
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Both ends of the edge, in pixels.
+    pub fn corners(&self, layout: &Layout) -> (Point, Point) {
+        layout.side_corners(self.coords, self.direction)
+    }
+}
+
+/// What stands on an [Edge].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EdgeKind {
+    /// Thin rock wall.
+    Wall,
+    ClosedDoor,
+    /// Doorway anyone can go and see through.
+    OpenDoor,
+}
+
+impl EdgeKind {
+    pub fn blocks_movement(self) -> bool {
+        matches!(self, Self::Wall | Self::ClosedDoor)
+    }
+
+    pub fn blocks_sight(self) -> bool {
+        matches!(self, Self::Wall | Self::ClosedDoor)
+    }
+
+    /// Color used to draw the edge.
+    pub fn color(self) -> Color {
+        match self {
+            Self::Wall => Color::RGB(60, 60, 60),
+            Self::ClosedDoor => Color::RGB(120, 70, 30),
+            Self::OpenDoor => Color::RGB(200, 160, 110),
+        }
+    }
+}
+
+impl Board {
+    /// What stands on the side of a tile facing `direction`.
+    pub fn edge(&self, coords: Coordinates, direction: Direction) -> Option<EdgeKind> {
+        self.edges.get(&Edge::new(coords, direction)).copied()
+    }
+
+    /// What stands between two tiles. None if there is nothing, or if they aren't neighbours.
+    pub fn edge_between(&self, from: Coordinates, to: Coordinates) -> Option<EdgeKind> {
+        Edge::between(from, to).and_then(|edge| self.edges.get(&edge).copied())
+    }
+
+    /// Build on, or clear with None, the side of a tile facing `direction`. Only sides between
+    /// two tiles of the board can hold something.
+    /// Emits [BoardEvent::EdgeChanged] if something else stood there.
+    pub fn set_edge(&mut self, coords: Coordinates, direction: Direction, kind: Option<EdgeKind>) {
+        let edge = Edge::new(coords, direction);
+        let (a, b) = edge.tiles();
+        if self.get(a).is_none() || self.get(b).is_none() {
+            return;
+        }
+        let old = match kind {
+            Some(kind) => self.edges.insert(edge, kind),
+            None => self.edges.remove(&edge),
+        };
+        if old != kind {
+            self.events.push(BoardEvent::EdgeChanged {
+                edge,
+                old,
+                new: kind,
+            });
+        }
+    }
+
+    /// Draw every wall and door as a thick line along its edge.
+    pub fn draw_edges<T>(&self, canvas: &mut Canvas<T>, layout: &Layout)
+    where
+        T: RenderTarget,
+    {
+        for (edge, kind) in self.edges.iter() {
+            let (from, to) = edge.corners(layout);
+            canvas
+                .thick_line(
+                    from.x as i16,
+                    from.y as i16,
+                    to.x as i16,
+                    to.y as i16,
+                    5,
+                    kind.color(),
+                )
+                .unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized() {
+        let coords = Coordinates { q: 2, r: -1 };
+        for direction in Direction::ALL {
+            let edge = Edge::new(coords, direction);
+            let other_side = Edge::new(coords + direction, direction.opposite());
+
+            assert_eq!(edge, other_side);
+            assert_eq!(Edge::between(coords, coords + direction), Some(edge));
+            let (a, b) = edge.tiles();
+            assert!(
+                (a, b) == (coords, coords + direction) || (b, a) == (coords, coords + direction)
+            );
+        }
+        assert_eq!(
+            Edge::between(coords, coords + Coordinates { q: 2, r: 0 }),
+            None
+        );
+    }
+
+    #[test]
+    fn saved_edges_are_normalized() {
+        let edge: Edge =
+            serde_json::from_str(r#"{"coords": {"q": 0, "r": 0}, "direction": "Left"}"#).unwrap();
+        assert_eq!(
+            edge,
+            Edge::new(Coordinates { q: -1, r: 0 }, Direction::Right)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::edge::{Edge, EdgeKind};
+use super::occupant::Occupant;
+use super::tile::TileKind;
+use serde::{Deserialize, Serialize};
+
+/// Something that changed on a [Board].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BoardEvent {
+    /// A tile is now made of something else.
+    TileChanged {
+        coords: Coordinates,
+        old: TileKind,
+        new: TileKind,
+    },
+    /// The ground of a tile is now at another height.
+    HeightChanged {
+        coords: Coordinates,
+        old: i32,
+        new: i32,
+    },
+    /// Something was built on, or removed from, the side between two tiles.
+    EdgeChanged {
+        edge: Edge,
+        old: Option<EdgeKind>,
+        new: Option<EdgeKind>,
+    },
+    OccupantPlaced {
+        occupant: Occupant,
+        coords: Coordinates,
+    },
+    OccupantMoved {
+        occupant: Occupant,
+        from: Coordinates,
+        to: Coordinates,
+    },
+    OccupantRemoved {
+        occupant: Occupant,
+        coords: Coordinates,
+    },
+}
+
+impl BoardEvent {
+    /// Tiles whose terrain or occupants changed.
+    pub fn coordinates(&self) -> Vec<Coordinates> {
+        match self {
+            Self::TileChanged { coords, .. }
+            | Self::HeightChanged { coords, .. }
+            | Self::OccupantPlaced { coords, .. }
+            | Self::OccupantRemoved { coords, .. } => vec![*coords],
+            Self::OccupantMoved { from, to, .. } => vec![*from, *to],
+            Self::EdgeChanged { edge, .. } => {
+                let (a, b) = edge.tiles();
+                vec![a, b]
+            }
+        }
+    }
+}
+
+/// Events emitted by a [Board] and not handled yet, oldest first.
+///
+/// Pending events are not part of the board's state: they are not saved, and boards are equal
+/// whatever their queues hold.
+#[derive(Debug, Default, Clone)]
+pub struct EventQueue {
+    events: Vec<BoardEvent>,
+}
+
+impl EventQueue {
+    pub fn push(&mut self, event: BoardEvent) {
+        self.events.push(event);
+    }
+
+    /// Take every pending event, leaving the queue empty.
+    pub fn drain(&mut self) -> Vec<BoardEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Pending events, without taking them.
+    pub fn pending(&self) -> &[BoardEvent] {
+        &self.events
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+/// Every event of a game, kept for auditing or replays. Can be saved like a [Board].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventLog {
+    /// Events in the order they happened, with the turn they happened on.
+    pub entries: Vec<(u32, BoardEvent)>,
+}
+
+impl EventLog {
+    /// Move every pending event of the board to the log.
+    pub fn record(&mut self, turn: u32, board: &mut Board) {
+        self.entries
+            .extend(board.drain_events().into_iter().map(|event| (turn, event)));
+    }
+
+    /// Every event that happened on a tile, oldest first.
+    pub fn at(&self, coords: Coordinates) -> impl Iterator<Item = &(u32, BoardEvent)> + '_ {
+        self.entries
+            .iter()
+            .filter(move |(_, event)| event.coordinates().contains(&coords))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::occupant::OccupantKind;
+    use crate::board::outline::Outline;
+
+    #[test]
+    fn changes_emit_events() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 4,
+            height: 4,
+        });
+        let a = Coordinates::from_offset(1, 1);
+        let b = Coordinates::from_offset(2, 1);
+
+        board.fill(a);
+        // Nothing changes, so nothing is emitted.
+        board.fill(a);
+        board.free(Coordinates::from_offset(9, 9));
+        let dwarf = board.place(b, OccupantKind::Dwarf).unwrap();
+        board.move_occupant(dwarf.id, a).unwrap_err();
+        board.free(a);
+        board.move_occupant(dwarf.id, a).unwrap();
+        board.remove_occupant(dwarf.id);
+
+        assert_eq!(
+            board.drain_events(),
+            vec![
+                BoardEvent::TileChanged {
+                    coords: a,
+                    old: TileKind::Floor,
+                    new: TileKind::Rock
+                },
+                BoardEvent::OccupantPlaced {
+                    occupant: dwarf,
+                    coords: b
+                },
+                BoardEvent::TileChanged {
+                    coords: a,
+                    old: TileKind::Rock,
+                    new: TileKind::Floor
+                },
+                BoardEvent::OccupantMoved {
+                    occupant: dwarf,
+                    from: b,
+                    to: a
+                },
+                BoardEvent::OccupantRemoved {
+                    occupant: dwarf,
+                    coords: a
+                },
+            ]
+        );
+        assert!(board.drain_events().is_empty());
+    }
+
+    #[test]
+    fn log() {
+        let mut board = Board::from_outline(&Outline::Hexagon { radius: 2 });
+        let mut log = EventLog::default();
+        let coords = Coordinates::from_offset(2, 2);
+
+        board.fill(coords);
+        log.record(1, &mut board);
+        board.set(coords, TileKind::Lava);
+        board.fill(Coordinates::from_offset(1, 2));
+        log.record(2, &mut board);
+
+        assert_eq!(log.entries.len(), 3);
+        assert_eq!(
+            log.at(coords).map(|(turn, _)| *turn).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+
+        let saved = serde_json::to_string(&log).unwrap();
+        assert_eq!(serde_json::from_str::<EventLog>(&saved).unwrap(), log);
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::edge::EdgeKind;
+use super::event::BoardEvent;
+use super::layout::Layout;
+use super::occupant::Team;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+/// What a team knows about a tile.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Exploration {
+    /// Never seen by anyone of the team.
+    #[default]
+    Unknown,
+    /// Seen before, but nobody of the team sees it right now.
+    Seen,
+    /// In sight of a unit of the team.
+    Visible,
+}
+
+/// Exploration state of every tile, for each team.
+///
+/// A unit sees every tile it has a [line of sight][Board::line_of_sight] to. Units don't block
+/// sight, walls do.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FogOfWar {
+    #[serde(with = "super::save::by_coordinates")]
+    dwarves: HashMap<Coordinates, Exploration>,
+    #[serde(with = "super::save::by_coordinates")]
+    enemies: HashMap<Coordinates, Exploration>,
+}
+
+impl FogOfWar {
+    /// Fog where both teams already see everything their units can see.
+    pub fn new(board: &Board) -> Self {
+        let mut fog = FogOfWar::default();
+        fog.update(board);
+        fog
+    }
+
+    fn tiles(&self, team: Team) -> &HashMap<Coordinates, Exploration> {
+        match team {
+            Team::Dwarves => &self.dwarves,
+            Team::Enemies => &self.enemies,
+        }
+    }
+
+    fn tiles_mut(&mut self, team: Team) -> &mut HashMap<Coordinates, Exploration> {
+        match team {
+            Team::Dwarves => &mut self.dwarves,
+            Team::Enemies => &mut self.enemies,
+        }
+    }
+
+    /// What a team knows about a tile.
+    pub fn get(&self, team: Team, coords: Coordinates) -> Exploration {
+        self.tiles(team).get(&coords).copied().unwrap_or_default()
+    }
+
+    /// Recompute what every team sees. Tiles out of sight are remembered as [Exploration::Seen].
+    pub fn update(&mut self, board: &Board) {
+        for team in [Team::Dwarves, Team::Enemies] {
+            let units: Vec<Coordinates> = board
+                .occupants
+                .iter()
+                .filter(|(_, occupant)| occupant.kind.team() == Some(team))
+                .map(|(coords, _)| coords)
+                .collect();
+
+            let tiles = self.tiles_mut(team);
+            for state in tiles.values_mut() {
+                if *state == Exploration::Visible {
+                    *state = Exploration::Seen;
+                }
+            }
+            for unit in units.iter() {
+                for coords in board.field_of_view(*unit, None, |_| false) {
+                    tiles.insert(coords, Exploration::Visible);
+                }
+            }
+        }
+    }
+
+    /// Update the fog if any of the events changes what units can see.
+    /// Returns true if it was updated.
+    pub fn handle(&mut self, board: &Board, events: &[BoardEvent]) -> bool {
+        let stale = events.iter().any(|event| match event {
+            BoardEvent::TileChanged { old, new, .. } => old.is_opaque() != new.is_opaque(),
+            BoardEvent::HeightChanged { .. } => true,
+            BoardEvent::EdgeChanged { old, new, .. } => {
+                old.is_some_and(EdgeKind::blocks_sight) != new.is_some_and(EdgeKind::blocks_sight)
+            }
+            BoardEvent::OccupantPlaced { occupant, .. }
+            | BoardEvent::OccupantMoved { occupant, .. }
+            | BoardEvent::OccupantRemoved { occupant, .. } => occupant.kind.team().is_some(),
+        });
+        if stale {
+            self.update(board);
+        }
+        stale
+    }
+
+    /// Darken unknown tiles and dim remembered ones, as seen by `team`.
+    pub fn draw<T>(&self, canvas: &mut Canvas<T>, layout: &Layout, board: &Board, team: Team)
+    where
+        T: RenderTarget,
+    {
+        for (coords, tile) in board.iter() {
+            match self.get(team, coords) {
+                Exploration::Unknown => tile.mask(canvas, layout, coords, Color::RGB(20, 20, 20)),
+                Exploration::Seen => {
+                    tile.mask(canvas, layout, coords, Color::RGBA(20, 20, 20, 140))
+                }
+                Exploration::Visible => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::occupant::OccupantKind;
+
+    const MAP: &str = "\
+. . # . .
+ . . # . .
+. . . # . .
+";
+
+    #[test]
+    fn exploration() {
+        let mut board: Board = MAP.parse().unwrap();
+        let start = Coordinates::from_offset(0, 0);
+        let behind_wall = Coordinates::from_offset(4, 0);
+        let dwarf = board.place(start, OccupantKind::Dwarf).unwrap();
+        let mut fog = FogOfWar::new(&board);
+
+        assert_eq!(fog.get(Team::Dwarves, start), Exploration::Visible);
+        assert_eq!(fog.get(Team::Dwarves, behind_wall), Exploration::Unknown);
+        // Nobody on the other team.
+        assert_eq!(fog.get(Team::Enemies, start), Exploration::Unknown);
+
+        // Dig through, and walk to the other side.
+        board.free(Coordinates::from_offset(2, 0));
+        let events = board.drain_events();
+        assert!(fog.handle(&board, &events));
+        assert_eq!(fog.get(Team::Dwarves, behind_wall), Exploration::Visible);
+
+        board.fill(Coordinates::from_offset(2, 0));
+        board
+            .move_occupant(dwarf.id, Coordinates::from_offset(5, 2))
+            .unwrap();
+        let events = board.drain_events();
+        assert!(fog.handle(&board, &events));
+        assert_eq!(fog.get(Team::Dwarves, start), Exploration::Seen);
+        assert_eq!(fog.get(Team::Dwarves, behind_wall), Exploration::Visible);
+
+        // Items don't see anything.
+        board
+            .place(Coordinates::from_offset(0, 2), OccupantKind::Item)
+            .unwrap();
+        let events = board.drain_events();
+        assert!(!fog.handle(&board, &events));
+    }
+}
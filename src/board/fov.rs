@@ -0,0 +1,456 @@
+use std::collections::HashSet;
+use std::f64::consts::PI;
+
+use super::board::Board;
+use super::coordinates::{Coordinates, FloatCoordinates};
+use super::direction::Direction;
+use super::edge::EdgeKind;
+use super::occupant::Occupant;
+
+/// Room left around shadows, so rounding errors never decide what is seen.
+const EPSILON: f64 = 1e-9;
+/// Number of slices the view from each corner is cut into, to only look at nearby shadows.
+const SLICES: usize = 64;
+
+/// Something that may stop sight: a tile, or a wall between two tiles.
+struct Blocker {
+    /// Corners of the blocker. Its shape is their convex hull.
+    points: Vec<FloatCoordinates>,
+    /// Tile the blocker is, if it is one.
+    tile: Option<Coordinates>,
+    /// Lines from ends both at most this high are stopped, lines from ends both higher go
+    /// over it.
+    below: i32,
+}
+
+impl Blocker {
+    /// Corners of a tile.
+    fn hex(coords: Coordinates, below: i32) -> Self {
+        let center = FloatCoordinates::from(coords);
+        Blocker {
+            points: (0..6).map(|i| center.corner(i)).collect(),
+            tile: Some(coords),
+            below,
+        }
+    }
+
+    /// Wall on the side of a tile.
+    fn side(coords: Coordinates, direction: Direction) -> Self {
+        let (a, b) = coords.side(direction);
+        Blocker {
+            points: vec![a, b],
+            tile: None,
+            below: i32::MAX,
+        }
+    }
+
+    /// Does it stop every line going through its tile, whatever the heights.
+    fn seals(&self) -> bool {
+        self.tile.is_some() && self.below == i32::MAX
+    }
+}
+
+/// A [Blocker], as seen from one corner of the origin.
+struct Shadow {
+    /// Direction of the middle of the blocker, in radians.
+    middle: f64,
+    /// Both sides of the blocker, in radians from the middle.
+    from: f64,
+    to: f64,
+    /// Distance to the closest point of the blocker.
+    near: f64,
+    /// Distance to the farthest point of the blocker. Lines longer than that, between both
+    /// sides, go all the way through it.
+    far: f64,
+    tile: Option<Coordinates>,
+    below: i32,
+}
+
+impl Shadow {
+    fn new(eye: FloatCoordinates, blocker: &Blocker) -> Self {
+        let count = blocker.points.len() as f64;
+        let center =
+            blocker
+                .points
+                .iter()
+                .fold(FloatCoordinates { q: 0.0, r: 0.0 }, |center, point| {
+                    FloatCoordinates {
+                        q: center.q + point.q / count,
+                        r: center.r + point.r / count,
+                    }
+                });
+        let middle = angle(eye, center);
+        let mut shadow = Shadow {
+            middle,
+            from: f64::MAX,
+            to: f64::MIN,
+            near: f64::MAX,
+            far: 0.0,
+            tile: blocker.tile,
+            below: blocker.below,
+        };
+        for (i, point) in blocker.points.iter().enumerate() {
+            let offset = wrap(angle(eye, *point) - middle);
+            let next = blocker.points[(i + 1) % blocker.points.len()];
+            shadow.from = shadow.from.min(offset);
+            shadow.to = shadow.to.max(offset);
+            shadow.near = shadow.near.min(segment_distance(eye, *point, next));
+            shadow.far = shadow.far.max(length(eye, *point));
+        }
+        shadow
+    }
+
+    /// Both sides of the shadow, in radians, with some room.
+    fn bounds(&self) -> (f64, f64) {
+        (
+            self.middle + self.from - EPSILON,
+            self.middle + self.to + EPSILON,
+        )
+    }
+
+    /// Does every line from the eye toward `angle`, `length` long, go through the blocker.
+    /// `top` is the highest end of the line.
+    fn hides(&self, angle: f64, length: f64, top: i32) -> bool {
+        let offset = wrap(angle - self.middle);
+        top <= self.below
+            && self.from + EPSILON < offset
+            && offset < self.to - EPSILON
+            && self.far < length
+    }
+
+    /// Could a line from the eye toward `angle`, `length` long, ending on `target`, touch the
+    /// blocker. `bottom` is the lowest end of the line.
+    fn may_touch(&self, angle: f64, length: f64, bottom: i32, target: Coordinates) -> bool {
+        let offset = wrap(angle - self.middle);
+        bottom <= self.below
+            && self.tile != Some(target)
+            && self.from - EPSILON <= offset
+            && offset <= self.to + EPSILON
+            && self.near <= length + EPSILON
+    }
+}
+
+/// Every [Shadow] seen from one corner of the origin, sorted in slices by direction.
+struct View {
+    eye: FloatCoordinates,
+    shadows: Vec<Shadow>,
+    slices: Vec<Vec<usize>>,
+}
+
+impl View {
+    fn new(eye: FloatCoordinates) -> Self {
+        View {
+            eye,
+            shadows: vec![],
+            slices: vec![vec![]; SLICES],
+        }
+    }
+
+    fn slice(angle: f64) -> usize {
+        let slice = ((wrap(angle) + PI) / (2.0 * PI) * SLICES as f64).floor() as i64;
+        slice.rem_euclid(SLICES as i64) as usize
+    }
+
+    fn add(&mut self, blocker: &Blocker) {
+        let shadow = Shadow::new(self.eye, blocker);
+        let (from, to) = shadow.bounds();
+        let (first, count) = (
+            Self::slice(from),
+            Self::slice(to) + SLICES - Self::slice(from),
+        );
+        for i in 0..=(count % SLICES) {
+            self.slices[(first + i) % SLICES].push(self.shadows.len());
+        }
+        self.shadows.push(shadow);
+    }
+
+    /// Every shadow that may be toward `angle`.
+    fn toward(&self, angle: f64) -> impl Iterator<Item = &Shadow> + '_ {
+        self.slices[Self::slice(angle)]
+            .iter()
+            .map(|i| &self.shadows[*i])
+    }
+}
+
+fn angle(from: FloatCoordinates, to: FloatCoordinates) -> f64 {
+    (to.r - from.r).atan2(to.q - from.q)
+}
+
+fn length(from: FloatCoordinates, to: FloatCoordinates) -> f64 {
+    (to.q - from.q).hypot(to.r - from.r)
+}
+
+/// Distance from `point` to the segment from `a` to `b`.
+fn segment_distance(point: FloatCoordinates, a: FloatCoordinates, b: FloatCoordinates) -> f64 {
+    let (dq, dr) = (b.q - a.q, b.r - a.r);
+    let t = ((point.q - a.q) * dq + (point.r - a.r) * dr) / (dq * dq + dr * dr);
+    let t = t.clamp(0.0, 1.0);
+    length(
+        point,
+        FloatCoordinates {
+            q: a.q + dq * t,
+            r: a.r + dr * t,
+        },
+    )
+}
+
+/// Bring an angle back between -PI and PI.
+fn wrap(angle: f64) -> f64 {
+    if angle > PI {
+        angle - 2.0 * PI
+    } else if angle <= -PI {
+        angle + 2.0 * PI
+    } else {
+        angle
+    }
+}
+
+/// What [sweep] needs to know about the board.
+struct Rules<B, H, S, C> {
+    /// What a tile may stop sight with: the tile itself and the walls on its sides.
+    blockers: B,
+    /// Ground height of a tile.
+    height: H,
+    /// Can the tile itself be seen, when nothing is in the way. Must be cheap.
+    seeable: S,
+    /// The exact sight check, for the few tiles shadows don't settle.
+    clear: C,
+}
+
+/// Sweep the view from `origin`, ring after ring, see [Coordinates::field_of_view].
+///
+/// Shadows of the blockers seen so far settle most tiles: hidden if every line to them goes
+/// all the way through a blocker, seen if one line can't touch any. Stops after `range`
+/// rings, or after a ring where every tile stops sight whatever the heights.
+fn sweep<B, H, S, C>(
+    origin: Coordinates,
+    range: Option<u32>,
+    rules: Rules<B, H, S, C>,
+) -> HashSet<Coordinates>
+where
+    B: Fn(Coordinates) -> Vec<Blocker>,
+    H: Fn(Coordinates) -> i32,
+    S: Fn(Coordinates) -> bool,
+    C: Fn(Coordinates) -> bool,
+{
+    let mut visible: HashSet<Coordinates> = HashSet::new();
+    if !(rules.clear)(origin) {
+        return visible;
+    }
+    visible.insert(origin);
+
+    let mut views: Vec<View> = (0..6).map(|i| View::new(origin.sight_corner(i))).collect();
+    let add = |views: &mut Vec<View>, coords: Coordinates| {
+        let blockers = (rules.blockers)(coords);
+        for blocker in blockers.iter() {
+            if blocker.tile != Some(origin) {
+                views.iter_mut().for_each(|view| view.add(blocker));
+            }
+        }
+        blockers.iter().any(Blocker::seals)
+    };
+    let add_ring = |views: &mut Vec<View>, radius: u32| {
+        let mut sealed = true;
+        for coords in origin.ring(radius) {
+            sealed &= add(views, coords);
+        }
+        sealed
+    };
+    // Lines to a tile may touch tiles one ring farther, so blockers are added a ring ahead.
+    add(&mut views, origin);
+    let mut sealed = add_ring(&mut views, 1);
+
+    let origin_height = (rules.height)(origin);
+    let mut radius = 1;
+    while range.is_none_or(|range| radius <= range) {
+        let next_sealed = add_ring(&mut views, radius + 1);
+
+        for target in origin.ring(radius) {
+            if !(rules.seeable)(target) {
+                continue;
+            }
+            let height = (rules.height)(target);
+            let (top, bottom) = (origin_height.max(height), origin_height.min(height));
+            let (mut hidden, mut seen) = (true, false);
+            'lines: for view in views.iter() {
+                for i in 0..6 {
+                    let corner = target.sight_corner(i);
+                    let (angle, length) = (angle(view.eye, corner), length(view.eye, corner));
+                    let mut shadows = view.toward(angle);
+                    if shadows
+                        .by_ref()
+                        .all(|shadow| !shadow.may_touch(angle, length, bottom, target))
+                    {
+                        seen = true;
+                        break 'lines;
+                    }
+                    hidden &= view
+                        .toward(angle)
+                        .any(|shadow| shadow.hides(angle, length, top));
+                }
+            }
+            if seen || (!hidden && (rules.clear)(target)) {
+                visible.insert(target);
+            }
+        }
+
+        if sealed {
+            break;
+        }
+        sealed = next_sealed;
+        radius += 1;
+    }
+
+    visible
+}
+
+impl Coordinates {
+    /// Every tile this one has a [line of sight][Coordinates::strict_line_by] to, at most `range`
+    /// tiles away, in a single sweep. `opaque` tiles block sight, and are never visible.
+    ///
+    /// Without a range, the sweep stops at the first ring of opaque tiles, so tiles off the
+    /// board should be opaque.
+    pub fn field_of_view<F>(self, range: Option<u32>, opaque: F) -> HashSet<Coordinates>
+    where
+        F: Fn(Coordinates) -> bool,
+    {
+        let opaque = &opaque;
+        sweep(
+            self,
+            range,
+            Rules {
+                blockers: |coords| {
+                    if opaque(coords) {
+                        vec![Blocker::hex(coords, i32::MAX)]
+                    } else {
+                        vec![]
+                    }
+                },
+                height: |_| 0,
+                seeable: |coords| !opaque(coords),
+                clear: |target| self.strict_line_by(target, |coords| !opaque(coords)),
+            },
+        )
+    }
+}
+
+impl Board {
+    /// Every tile `from` has a [line of sight][Board::line_of_sight] to, at most `range` tiles
+    /// away, in a single sweep.
+    pub fn field_of_view<F>(
+        &self,
+        from: Coordinates,
+        range: Option<u32>,
+        blocks: F,
+    ) -> HashSet<Coordinates>
+    where
+        F: Fn(&Occupant) -> bool,
+    {
+        let blocks = &blocks;
+        let farthest = self
+            .iter()
+            .map(|(coords, _)| from.distance(coords))
+            .max()
+            .unwrap_or(0);
+        sweep(
+            from,
+            Some(range.map_or(farthest, |range| range.min(farthest))),
+            Rules {
+                blockers: |coords| {
+                    let tile = match self.get(coords) {
+                        Some(tile) => tile,
+                        None => return vec![Blocker::hex(coords, i32::MAX)],
+                    };
+                    // Lines going over the tile miss what stands on it, and lines lower than
+                    // its ground go through it.
+                    let below =
+                        if tile.kind.is_opaque() || self.occupants_at(coords).iter().any(blocks) {
+                            tile.top()
+                        } else {
+                            tile.height - 1
+                        };
+                    let mut blockers = vec![Blocker::hex(coords, below)];
+                    for direction in Direction::ALL {
+                        if self
+                            .edge(coords, direction)
+                            .is_some_and(EdgeKind::blocks_sight)
+                        {
+                            blockers.push(Blocker::side(coords, direction));
+                        }
+                    }
+                    blockers
+                },
+                height: |coords| self.get(coords).map_or(0, |tile| tile.height),
+                seeable: |coords| self.get(coords).is_some_and(|tile| !tile.kind.is_opaque()),
+                clear: |target| self.line_of_sight(from, target, blocks),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::board::BoardConfig;
+    use crate::board::edge::EdgeKind;
+    use crate::board::occupant::OccupantKind;
+    use crate::board::outline::Outline;
+
+    fn board(seed: u64) -> Board {
+        let mut board = Board::from_seed(
+            seed,
+            &BoardConfig {
+                outline: Outline::Hexagon { radius: 5 },
+                obstacle_ratio: 0.3,
+            },
+        );
+        let coords = board.sorted_coordinates();
+        for (i, coords) in coords.iter().enumerate() {
+            match i % 11 {
+                0 => board.set_height(*coords, 1),
+                4 => board.set_height(*coords, -1),
+                7 => board.set_edge(*coords, Direction::Right, Some(EdgeKind::Wall)),
+                9 => {
+                    let _ = board.place(*coords, OccupantKind::Enemy);
+                }
+                _ => (),
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn same_as_line_of_sight() {
+        for seed in 0..3 {
+            let board = board(seed);
+            let enemies = |occupant: &Occupant| occupant.kind == OccupantKind::Enemy;
+            for from in board.sorted_coordinates().into_iter().step_by(3) {
+                let expected: HashSet<Coordinates> = board
+                    .sorted_coordinates()
+                    .into_iter()
+                    .filter(|to| board.line_of_sight(from, *to, enemies))
+                    .collect();
+                assert_eq!(
+                    board.field_of_view(from, None, enemies),
+                    expected,
+                    "{}",
+                    from
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn same_as_strict_line() {
+        let board = board(4);
+        let opaque = |coords| board.get(coords).is_none_or(|tile| tile.kind.is_opaque());
+        for from in board.sorted_coordinates().into_iter().step_by(4) {
+            let view = from.field_of_view(Some(3), opaque);
+            for to in from.range(4) {
+                let expected =
+                    from.distance(to) <= 3 && from.strict_line_by(to, |coords| !opaque(coords));
+                assert_eq!(view.contains(&to), expected, "{} / {}", from, to);
+            }
+        }
+    }
+}
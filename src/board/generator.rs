@@ -0,0 +1,345 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::direction::Direction;
+use rand::rngs::SmallRng;
+use rand::Rng;
+
+/// How many times [Board::generate] will roll a map before giving up on connecting it.
+pub const MAX_ATTEMPTS: u32 = 8;
+
+/// Turns an outline where every tile is free into a cave.
+///
+/// Generators only have to care about the look of the cave:
+/// [Board::generate] takes care of joining disconnected parts afterwards.
+pub trait Generator {
+    fn generate(&self, board: &mut Board, rng: &mut SmallRng);
+}
+
+/// Every tile has the same independent chance of being an obstacle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Noise {
+    /// Chance for a tile to be an obstacle, see [chance].
+    pub obstacle_ratio: f64,
+}
+
+impl Generator for Noise {
+    /// The first tile, row by row, is always an obstacle.
+    fn generate(&self, board: &mut Board, rng: &mut SmallRng) {
+        let coords = board.sorted_coordinates();
+        for coord in coords.iter() {
+            if !rng.gen_bool(1.0 - chance(self.obstacle_ratio)) {
+                board.fill(*coord);
+            }
+        }
+        if let Some(first) = coords.first() {
+            board.fill(*first);
+        }
+    }
+}
+
+/// A ratio as a probability: clamped between 0 and 1, and 0 if it is not a number.
+fn chance(ratio: f64) -> f64 {
+    if ratio.is_nan() {
+        0.0
+    } else {
+        ratio.clamp(0.0, 1.0)
+    }
+}
+
+/// Random fill, then smoothed a few times so obstacles clump into walls and open tiles into rooms.
+///
+/// On each pass, tiles outside the board count as walls, which thickens the cave's edges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellularAutomata {
+    /// Chance for a tile to start as a wall, see [chance].
+    pub fill_ratio: f64,
+    /// Number of smoothing passes.
+    pub iterations: u32,
+    /// A free tile with at least that many walls around it becomes a wall.
+    pub birth: usize,
+    /// A wall with at least that many walls around it stays a wall.
+    pub survival: usize,
+}
+
+impl Default for CellularAutomata {
+    fn default() -> Self {
+        CellularAutomata {
+            fill_ratio: 0.45,
+            iterations: 4,
+            birth: 4,
+            survival: 3,
+        }
+    }
+}
+
+impl Generator for CellularAutomata {
+    fn generate(&self, board: &mut Board, rng: &mut SmallRng) {
+        let coords = board.sorted_coordinates();
+        for coord in coords.iter() {
+            if rng.gen_bool(chance(self.fill_ratio)) {
+                board.fill(*coord);
+            }
+        }
+
+        for _ in 0..self.iterations {
+            let walls: HashMap<Coordinates, usize> = coords
+                .iter()
+                .map(|coord| (*coord, walls_around(board, *coord)))
+                .collect();
+
+            for coord in coords.iter() {
+                let free = board.tiles[coord].kind.is_walkable();
+                if (free && walls[coord] >= self.birth) || (!free && walls[coord] >= self.survival)
+                {
+                    board.fill(*coord);
+                } else {
+                    board.free(*coord);
+                }
+            }
+        }
+    }
+}
+
+/// Count walls and missing tiles around a tile.
+fn walls_around(board: &Board, coords: Coordinates) -> usize {
+    (0..6)
+        .map(|direction| board.get(coords + Direction::from(direction)))
+        .filter(|tile| !matches!(tile, Some(tile) if tile.kind.is_walkable()))
+        .count()
+}
+
+/// Start from solid rock and dig tunnels with a random walk, opening a small room from time to time.
+///
+/// The digger never jumps, so the result is connected by construction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tunnels {
+    /// Stop digging once this share of the board is open.
+    pub open_ratio: f64,
+    /// Chance for each step to open every tile around the digger, see [chance].
+    pub room_chance: f64,
+}
+
+impl Default for Tunnels {
+    fn default() -> Self {
+        Tunnels {
+            open_ratio: 0.5,
+            room_chance: 0.05,
+        }
+    }
+}
+
+impl Generator for Tunnels {
+    fn generate(&self, board: &mut Board, rng: &mut SmallRng) {
+        let coords = board.sorted_coordinates();
+        if coords.is_empty() {
+            return;
+        }
+        for coord in coords.iter() {
+            board.fill(*coord);
+        }
+
+        let target = (coords.len() as f64 * self.open_ratio).ceil() as usize;
+        let mut digger = coords[rng.gen_range(0..coords.len())];
+        let mut open = 0;
+        // Bail out eventually if the digger is stuck against the edges.
+        let mut steps = coords.len() * 100;
+
+        while open < target && steps > 0 {
+            steps -= 1;
+            if !board.tiles[&digger].kind.is_walkable() {
+                board.free(digger);
+                open += 1;
+            }
+            if rng.gen_bool(chance(self.room_chance)) {
+                for next in board.neighbours(digger) {
+                    if !board.tiles[&next].kind.is_walkable() {
+                        board.free(next);
+                        open += 1;
+                    }
+                }
+            }
+
+            let next = digger + Direction::from(rng.gen_range(0..6));
+            if board.get(next).is_some() {
+                digger = next;
+            }
+        }
+    }
+}
+
+/// Join every free region to the biggest one by digging the shortest corridor between them.
+///
+/// Returns false if it was impossible, either because the board has no free tile,
+/// or because its outline itself is split in several parts.
+pub fn connect(board: &mut Board) -> bool {
+    let mut regions = board.regions();
+    if regions.is_empty() {
+        return false;
+    }
+
+    while regions.len() > 1 {
+        match corridor(board, &regions[0]) {
+            Some(corridor) => corridor.into_iter().for_each(|coords| board.free(coords)),
+            None => return false,
+        }
+        regions = board.regions();
+    }
+
+    true
+}
+
+/// Shortest list of tiles to dig from `region` to the closest free tile outside of it.
+fn corridor(board: &Board, region: &[Coordinates]) -> Option<Vec<Coordinates>> {
+    let mut came_from: HashMap<Coordinates, Coordinates> = HashMap::new();
+    let mut frontier: VecDeque<Coordinates> = region.iter().copied().collect();
+    for coords in region.iter() {
+        came_from.insert(*coords, *coords);
+    }
+
+    while let Some(current) = frontier.pop_front() {
+        for next in board.neighbours(current) {
+            if came_from.contains_key(&next) {
+                continue;
+            }
+            came_from.insert(next, current);
+
+            if board.tiles[&next].kind.is_walkable() {
+                let mut corridor: Vec<Coordinates> = vec![];
+                let mut step = current;
+                while came_from[&step] != step {
+                    corridor.push(step);
+                    step = came_from[&step];
+                }
+                return Some(corridor);
+            }
+            frontier.push_back(next);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::board::BoardConfig;
+    use crate::board::outline::Outline;
+    use crate::board::tile::TileKind;
+
+    #[test]
+    fn generated_boards_are_connected() {
+        let config = BoardConfig::default();
+
+        for seed in 0..20 {
+            assert!(Board::generate(seed, &config, &CellularAutomata::default()).is_connected());
+            assert!(Board::generate(seed, &config, &Tunnels::default()).is_connected());
+            assert!(Board::generate(
+                seed,
+                &config,
+                &Noise {
+                    obstacle_ratio: 0.4
+                }
+            )
+            .is_connected());
+        }
+    }
+
+    #[test]
+    fn ratios_out_of_range() {
+        let config = BoardConfig::default();
+
+        for ratio in [-0.5, 1.5, f64::NAN] {
+            let noise = Noise {
+                obstacle_ratio: ratio,
+            };
+            let automata = CellularAutomata {
+                fill_ratio: ratio,
+                ..CellularAutomata::default()
+            };
+            let tunnels = Tunnels {
+                room_chance: ratio,
+                ..Tunnels::default()
+            };
+            Board::generate(0, &config, &noise);
+            Board::generate(0, &config, &automata);
+            assert!(Board::generate(0, &config, &tunnels).is_connected());
+        }
+    }
+
+    #[test]
+    fn noise_blocks_first_tile() {
+        // No tile at offset (0, 0).
+        let config = BoardConfig {
+            outline: Outline::Hexagon { radius: 2 },
+            obstacle_ratio: 0.0,
+        };
+        let board = Board::from_seed(0, &config);
+        assert!(board.get(Coordinates::from_offset(0, 0)).is_none());
+        let first = board.sorted_coordinates()[0];
+        assert_eq!(board.get(first).unwrap().kind, TileKind::Rock);
+        assert_eq!(
+            board
+                .iter()
+                .filter(|(_, tile)| !tile.kind.is_walkable())
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn split_outline_stays_disconnected() {
+        // Two islands no corridor can join.
+        let left = Coordinates { q: 0, r: 0 };
+        let right = Coordinates { q: 5, r: 0 };
+        let tiles = left.range(1).chain(right.range(1)).collect();
+        let config = BoardConfig {
+            outline: Outline::Mask(tiles),
+            obstacle_ratio: 0.0,
+        };
+
+        let board = Board::from_seed(0, &config);
+        assert!(!board.is_connected());
+        assert_eq!(board.regions().len(), 2);
+    }
+
+    #[test]
+    fn generation_is_deterministic() {
+        let config = BoardConfig::default();
+
+        assert_eq!(
+            Board::generate(3, &config, &CellularAutomata::default()),
+            Board::generate(3, &config, &CellularAutomata::default())
+        );
+        assert_eq!(
+            Board::generate(3, &config, &Tunnels::default()),
+            Board::generate(3, &config, &Tunnels::default())
+        );
+    }
+
+    #[test]
+    fn connect_digs_corridor() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 5,
+            height: 1,
+        });
+        board.fill(Coordinates::from_offset(1, 0));
+        board.fill(Coordinates::from_offset(2, 0));
+        assert_eq!(board.regions().len(), 2);
+
+        assert!(connect(&mut board));
+        assert!(board.is_connected());
+        assert!(board.tiles.values().all(|tile| tile.kind.is_walkable()));
+    }
+
+    #[test]
+    fn connect_needs_free_tiles() {
+        let mut board = Board::from_outline(&Outline::Hexagon { radius: 1 });
+        for coords in board.sorted_coordinates() {
+            board.fill(coords);
+        }
+
+        assert!(!connect(&mut board));
+    }
+}
@@ -0,0 +1,274 @@
+use std::ops::Index;
+
+use super::coordinates::Coordinates;
+use super::tile::{Tile, TileKind};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Dense storage for the [Tiles][Tile] of a board.
+///
+/// Tiles live in a flat vector covering the bounding rectangle of the board in offset coordinates,
+/// see [Coordinates::to_offset]. A bitmask tells which cells actually hold a tile, so boards
+/// with holes or odd outlines still work. Iteration goes row by row, so it is always in the
+/// same order.
+#[derive(Debug, Clone, Default)]
+pub struct TileGrid {
+    /// Offset coordinates of the top left cell.
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    cells: Vec<Tile>,
+    /// One bit per cell, set if the cell holds a tile.
+    valid: Vec<u64>,
+    len: usize,
+}
+
+/// What unused cells are filled with. Never handed out.
+const EMPTY: Tile = Tile {
+    kind: TileKind::Rock,
+    height: 0,
+};
+
+impl TileGrid {
+    /// Grid with room for every cell between both offset coordinates, inclusive.
+    fn with_bounds(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        let width = (right - left + 1).max(0);
+        let height = (bottom - top + 1).max(0);
+        let size = (width * height) as usize;
+
+        TileGrid {
+            left,
+            top,
+            width,
+            height,
+            cells: vec![EMPTY; size],
+            valid: vec![0; size.div_ceil(64)],
+            len: 0,
+        }
+    }
+
+    /// Position of the cell in the flat vector, even if it holds no tile.
+    /// None if it is outside of the grid.
+    pub fn index(&self, coords: Coordinates) -> Option<usize> {
+        let (x, y) = coords.to_offset();
+        let (x, y) = (x - self.left, y - self.top);
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        Some((y * self.width + x) as usize)
+    }
+
+    /// Number of cells, used or not. Every [index][TileGrid::index] is lower than this.
+    pub fn capacity(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn is_valid(&self, index: usize) -> bool {
+        self.valid[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn coordinates_at(&self, index: usize) -> Coordinates {
+        let index = index as i32;
+        Coordinates::from_offset(
+            self.left + index % self.width,
+            self.top + index / self.width,
+        )
+    }
+
+    pub fn get(&self, coords: &Coordinates) -> Option<&Tile> {
+        let index = self.index(*coords)?;
+        if self.is_valid(index) {
+            Some(&self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, coords: &Coordinates) -> Option<&mut Tile> {
+        let index = self.index(*coords)?;
+        if self.is_valid(index) {
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// Add or replace a tile, growing the grid if needed. Returns the replaced tile.
+    pub fn insert(&mut self, coords: Coordinates, tile: Tile) -> Option<Tile> {
+        let index = match self.index(coords) {
+            Some(index) => index,
+            None => {
+                self.grow(coords);
+                self.index(coords).expect("grid should contain coordinates")
+            }
+        };
+
+        let previous = if self.is_valid(index) {
+            Some(self.cells[index])
+        } else {
+            self.valid[index / 64] |= 1 << (index % 64);
+            self.len += 1;
+            None
+        };
+        self.cells[index] = tile;
+        previous
+    }
+
+    /// Remove a tile, returning it.
+    pub fn remove(&mut self, coords: &Coordinates) -> Option<Tile> {
+        let index = self.index(*coords)?;
+        if !self.is_valid(index) {
+            return None;
+        }
+        self.valid[index / 64] &= !(1 << (index % 64));
+        self.len -= 1;
+        Some(self.cells[index])
+    }
+
+    /// Make room for `coords`, keeping every tile.
+    fn grow(&mut self, coords: Coordinates) {
+        let (x, y) = coords.to_offset();
+        let mut grid = if self.capacity() == 0 {
+            TileGrid::with_bounds(x, y, x, y)
+        } else {
+            TileGrid::with_bounds(
+                self.left.min(x),
+                self.top.min(y),
+                (self.left + self.width - 1).max(x),
+                (self.top + self.height - 1).max(y),
+            )
+        };
+
+        for (coords, tile) in self.iter() {
+            grid.insert(coords, *tile);
+        }
+        *self = grid;
+    }
+
+    /// Iterate over every tile, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, &Tile)> + '_ {
+        (0..self.cells.len())
+            .filter(|index| self.is_valid(*index))
+            .map(|index| (self.coordinates_at(index), &self.cells[index]))
+    }
+
+    /// Coordinates of every tile, row by row.
+    pub fn keys(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.iter().map(|(coords, _)| coords)
+    }
+
+    /// Every tile, row by row.
+    pub fn values(&self) -> impl Iterator<Item = &Tile> + '_ {
+        self.iter().map(|(_, tile)| tile)
+    }
+}
+
+impl Index<&Coordinates> for TileGrid {
+    type Output = Tile;
+
+    fn index(&self, coords: &Coordinates) -> &Self::Output {
+        self.get(coords).expect("no tile at coordinates")
+    }
+}
+
+impl PartialEq for TileGrid {
+    /// Grids are equal if they hold the same tiles, whatever their bounds.
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self
+                .iter()
+                .all(|(coords, tile)| other.get(&coords) == Some(tile))
+    }
+}
+
+impl FromIterator<(Coordinates, Tile)> for TileGrid {
+    fn from_iter<T: IntoIterator<Item = (Coordinates, Tile)>>(iter: T) -> Self {
+        let tiles: Vec<(Coordinates, Tile)> = iter.into_iter().collect();
+        let offsets: Vec<(i32, i32)> = tiles.iter().map(|(coords, _)| coords.to_offset()).collect();
+
+        let mut grid = TileGrid::with_bounds(
+            offsets.iter().map(|(x, _)| *x).min().unwrap_or(0),
+            offsets.iter().map(|(_, y)| *y).min().unwrap_or(0),
+            offsets.iter().map(|(x, _)| *x).max().unwrap_or(-1),
+            offsets.iter().map(|(_, y)| *y).max().unwrap_or(-1),
+        );
+        for (coords, tile) in tiles {
+            grid.insert(coords, tile);
+        }
+        grid
+    }
+}
+
+impl Serialize for TileGrid {
+    /// Same format as a map of [Coordinates] saved with [by_coordinates][super::save::by_coordinates].
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut pairs: Vec<(Coordinates, &Tile)> = self.iter().collect();
+        pairs.sort_by_key(|(coords, _)| (coords.r, coords.q));
+        serializer.collect_seq(pairs)
+    }
+}
+
+impl<'de> Deserialize<'de> for TileGrid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pairs: Vec<(Coordinates, Tile)> = Vec::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut grid = TileGrid::default();
+        let water = Tile::new(TileKind::Water);
+        let a = Coordinates::from_offset(3, 2);
+        let b = Coordinates::from_offset(-2, 5);
+
+        assert_eq!(grid.insert(a, water), None);
+        assert_eq!(grid.insert(b, Tile::new(TileKind::Floor)), None);
+        assert_eq!(grid.iter().count(), 2);
+        assert_eq!(grid.get(&a), Some(&water));
+        assert_eq!(grid[&b].kind, TileKind::Floor);
+        // Inside the bounds, but no tile.
+        assert_eq!(grid.get(&Coordinates::from_offset(0, 3)), None);
+        assert_eq!(grid.get(&Coordinates::from_offset(9, 9)), None);
+
+        assert_eq!(grid.insert(a, Tile::new(TileKind::Lava)), Some(water));
+        assert_eq!(grid.remove(&a), Some(Tile::new(TileKind::Lava)));
+        assert_eq!(grid.remove(&a), None);
+        assert_eq!(grid.iter().count(), 1);
+        assert_eq!(grid.keys().collect::<Vec<_>>(), vec![b]);
+    }
+
+    #[test]
+    fn iteration_order() {
+        let coords = [
+            Coordinates::from_offset(2, 1),
+            Coordinates::from_offset(0, 0),
+            Coordinates::from_offset(1, 1),
+            Coordinates::from_offset(4, 0),
+        ];
+        let grid: TileGrid = coords
+            .iter()
+            .map(|coords| (*coords, Tile::new(TileKind::Floor)))
+            .collect();
+
+        assert_eq!(
+            grid.keys().collect::<Vec<_>>(),
+            vec![coords[1], coords[3], coords[2], coords[0]]
+        );
+    }
+
+    #[test]
+    fn equality_ignores_bounds() {
+        let mut a = TileGrid::default();
+        a.insert(Coordinates::from_offset(10, 10), Tile::new(TileKind::Dirt));
+        let mut b = a.clone();
+        b.insert(Coordinates::from_offset(0, 0), Tile::new(TileKind::Dirt));
+        b.remove(&Coordinates::from_offset(0, 0));
+
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,140 @@
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::ops::Index;
+
+use super::coordinates::Coordinates;
+use super::tile::Tile;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Board tiles stored the way they used to be, every lookup going through a [HashMap], with the
+/// same API as [TileGrid][super::grid::TileGrid].
+///
+/// Boards use it instead of the grid with the `hashmap-tiles` feature, so benchmarks can compare
+/// both storages through the same [Board][super::board::Board] API.
+#[derive(Debug, Clone, Default)]
+pub struct HashTileGrid {
+    /// Position of each tile in `tiles`.
+    slots: HashMap<Coordinates, usize>,
+    /// Every tile, in no particular order.
+    tiles: Vec<(Coordinates, Tile)>,
+    /// Positions in `tiles`, row by row like the grid, so boards are generated the same way.
+    /// Sorted on the first iteration after a tile is added or removed.
+    order: OnceCell<Vec<usize>>,
+}
+
+impl HashTileGrid {
+    fn order(&self) -> &[usize] {
+        self.order.get_or_init(|| {
+            let mut order: Vec<usize> = (0..self.tiles.len()).collect();
+            order.sort_by_key(|index| {
+                let coords = self.tiles[*index].0;
+                (coords.r, coords.to_offset().0)
+            });
+            order
+        })
+    }
+
+    /// Position of the tile in the storage. None if there is no tile.
+    pub fn index(&self, coords: Coordinates) -> Option<usize> {
+        self.slots.get(&coords).copied()
+    }
+
+    /// Every [index][HashTileGrid::index] is lower than this.
+    pub fn capacity(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn get(&self, coords: &Coordinates) -> Option<&Tile> {
+        self.slots.get(coords).map(|index| &self.tiles[*index].1)
+    }
+
+    pub fn get_mut(&mut self, coords: &Coordinates) -> Option<&mut Tile> {
+        self.slots
+            .get(coords)
+            .map(|index| &mut self.tiles[*index].1)
+    }
+
+    /// Add or replace a tile. Returns the replaced tile.
+    pub fn insert(&mut self, coords: Coordinates, tile: Tile) -> Option<Tile> {
+        if let Some(previous) = self.get_mut(&coords) {
+            return Some(std::mem::replace(previous, tile));
+        }
+        self.slots.insert(coords, self.tiles.len());
+        self.tiles.push((coords, tile));
+        self.order.take();
+        None
+    }
+
+    /// Remove a tile, returning it.
+    pub fn remove(&mut self, coords: &Coordinates) -> Option<Tile> {
+        let index = self.slots.remove(coords)?;
+        let (_, tile) = self.tiles.swap_remove(index);
+        if let Some((moved, _)) = self.tiles.get(index) {
+            self.slots.insert(*moved, index);
+        }
+        self.order.take();
+        Some(tile)
+    }
+
+    /// Iterate over every tile, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, &Tile)> + '_ {
+        self.order().iter().map(|index| {
+            let (coords, tile) = &self.tiles[*index];
+            (*coords, tile)
+        })
+    }
+
+    /// Coordinates of every tile, row by row.
+    pub fn keys(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.iter().map(|(coords, _)| coords)
+    }
+
+    /// Every tile, row by row.
+    pub fn values(&self) -> impl Iterator<Item = &Tile> + '_ {
+        self.iter().map(|(_, tile)| tile)
+    }
+}
+
+impl Index<&Coordinates> for HashTileGrid {
+    type Output = Tile;
+
+    fn index(&self, coords: &Coordinates) -> &Self::Output {
+        self.get(coords).expect("no tile at coordinates")
+    }
+}
+
+impl PartialEq for HashTileGrid {
+    fn eq(&self, other: &Self) -> bool {
+        self.tiles.len() == other.tiles.len()
+            && self
+                .tiles
+                .iter()
+                .all(|(coords, tile)| other.get(coords) == Some(tile))
+    }
+}
+
+impl FromIterator<(Coordinates, Tile)> for HashTileGrid {
+    fn from_iter<T: IntoIterator<Item = (Coordinates, Tile)>>(iter: T) -> Self {
+        let mut grid = HashTileGrid::default();
+        for (coords, tile) in iter {
+            grid.insert(coords, tile);
+        }
+        grid
+    }
+}
+
+impl Serialize for HashTileGrid {
+    /// Same format as [TileGrid][super::grid::TileGrid].
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut pairs: Vec<(Coordinates, &Tile)> = self.iter().collect();
+        pairs.sort_by_key(|(coords, _)| (coords.r, coords.q));
+        serializer.collect_seq(pairs)
+    }
+}
+
+impl<'de> Deserialize<'de> for HashTileGrid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pairs: Vec<(Coordinates, Tile)> = Vec::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::layout::Layout;
+use super::occupant::Occupant;
+use super::tile::TileKind;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+
+/// Something dangerous spreading over the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HazardKind {
+    /// Burns on [flammable][TileKind::is_flammable] tiles, spreads to neighbouring ones and dies
+    /// out.
+    Fire,
+    /// [Lava][TileKind::Lava] tiles creeping over neighbouring floor. Lava is terrain, so it
+    /// never dies out.
+    Lava,
+    /// Poison cloud drifting from tile to tile until it thins out.
+    Gas,
+}
+
+impl HazardKind {
+    /// Chance for each neighbouring tile to catch the hazard, every round. None if the hazard
+    /// drifts instead of spreading.
+    pub fn spread_chance(self) -> Option<f64> {
+        match self {
+            Self::Fire => Some(0.3),
+            Self::Lava => Some(0.1),
+            Self::Gas => None,
+        }
+    }
+
+    /// Rounds before the hazard is gone. None if it stays forever.
+    pub fn lifetime(self) -> Option<u32> {
+        match self {
+            Self::Fire => Some(3),
+            Self::Lava => None,
+            Self::Gas => Some(5),
+        }
+    }
+
+    /// Can the hazard be on a tile of this kind.
+    pub fn can_cover(self, kind: TileKind) -> bool {
+        match self {
+            Self::Fire => kind.is_flammable(),
+            Self::Lava => kind == TileKind::Floor,
+            Self::Gas => !kind.is_opaque() && kind != TileKind::Lava,
+        }
+    }
+
+    /// Color drawn over tiles holding the hazard.
+    pub fn color(self) -> Color {
+        match self {
+            Self::Fire => Color::RGBA(255, 120, 0, 150),
+            Self::Lava => Color::RGBA(230, 90, 20, 150),
+            Self::Gas => Color::RGBA(90, 200, 40, 120),
+        }
+    }
+}
+
+/// Fire or gas on a tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hazard {
+    pub kind: HazardKind,
+    pub rounds_left: u32,
+}
+
+/// What happened during a round.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HazardReport {
+    pub round: u32,
+    /// Tiles whose hazard changed, with the hazard they hold now, row by row.
+    pub changed: Vec<(Coordinates, Option<HazardKind>)>,
+    /// Occupants standing in a hazard at the end of the round, by id.
+    pub affected: Vec<(Occupant, Coordinates, HazardKind)>,
+}
+
+/// Every hazard of a board, moving forward one round at a time.
+///
+/// Spreading is random, but the same seed and the same board always give the same rounds.
+#[derive(Debug, Clone)]
+pub struct HazardSystem {
+    hazards: HashMap<Coordinates, Hazard>,
+    rng: SmallRng,
+    pub round: u32,
+}
+
+impl HazardSystem {
+    pub fn new(seed: u64) -> Self {
+        HazardSystem {
+            hazards: HashMap::new(),
+            rng: SmallRng::seed_from_u64(seed),
+            round: 0,
+        }
+    }
+
+    /// Hazard on a tile. Lava is terrain, see [Board::get].
+    pub fn get(&self, coords: Coordinates) -> Option<Hazard> {
+        self.hazards.get(&coords).copied()
+    }
+
+    /// Start a fire or release gas on a tile. Lava is added by [setting][Board::set] terrain.
+    /// Returns false if the hazard can't be there.
+    pub fn ignite(&mut self, board: &Board, coords: Coordinates, kind: HazardKind) -> bool {
+        let lifetime = match (kind.lifetime(), board.get(coords)) {
+            (Some(lifetime), Some(tile)) if kind.can_cover(tile.kind) => lifetime,
+            _ => return false,
+        };
+        self.hazards.insert(
+            coords,
+            Hazard {
+                kind,
+                rounds_left: lifetime,
+            },
+        );
+        true
+    }
+
+    /// Hazard covering a tile, terrain included.
+    fn at(&self, board: &Board, coords: Coordinates) -> Option<HazardKind> {
+        match board.get(coords) {
+            Some(tile) if tile.kind == TileKind::Lava => Some(HazardKind::Lava),
+            _ => self.get(coords).map(|hazard| hazard.kind),
+        }
+    }
+
+    /// Roll whether the hazard spreads to a neighbouring tile.
+    fn spreads(&mut self, kind: HazardKind) -> bool {
+        kind.spread_chance()
+            .is_some_and(|chance| self.rng.gen_bool(chance))
+    }
+
+    /// Move every hazard forward one round: lava creeps, fire spreads and burns out, gas drifts
+    /// and thins out.
+    pub fn step(&mut self, board: &mut Board) -> HazardReport {
+        self.round += 1;
+        let before: HashMap<Coordinates, Option<HazardKind>> = board
+            .sorted_coordinates()
+            .into_iter()
+            .map(|coords| (coords, self.at(board, coords)))
+            .collect();
+
+        // Everything spreads from where it was at the start of the round.
+        let mut lava: Vec<Coordinates> = vec![];
+        let mut hazards: HashMap<Coordinates, Hazard> = HashMap::new();
+        for coords in board.sorted_coordinates() {
+            match before[&coords] {
+                Some(HazardKind::Lava) => {
+                    for next in board.neighbours(coords) {
+                        if HazardKind::Lava.can_cover(board.tiles[&next].kind)
+                            && self.spreads(HazardKind::Lava)
+                        {
+                            lava.push(next);
+                        }
+                    }
+                }
+                Some(HazardKind::Fire) => {
+                    let hazard = self.hazards[&coords];
+                    for next in board.neighbours(coords) {
+                        if before[&next].is_none()
+                            && HazardKind::Fire.can_cover(board.tiles[&next].kind)
+                            && self.spreads(HazardKind::Fire)
+                        {
+                            hazards.entry(next).or_insert(Hazard {
+                                kind: HazardKind::Fire,
+                                rounds_left: HazardKind::Fire.lifetime().unwrap_or(1),
+                            });
+                        }
+                    }
+                    if hazard.rounds_left > 1 {
+                        hazards.insert(
+                            coords,
+                            Hazard {
+                                rounds_left: hazard.rounds_left - 1,
+                                ..hazard
+                            },
+                        );
+                    }
+                }
+                Some(HazardKind::Gas) => {
+                    let hazard = self.hazards[&coords];
+                    let options: Vec<Coordinates> = board
+                        .neighbours(coords)
+                        .into_iter()
+                        .filter(|next| HazardKind::Gas.can_cover(board.tiles[next].kind))
+                        .collect();
+                    let drifted = if options.is_empty() {
+                        coords
+                    } else {
+                        options[self.rng.gen_range(0..options.len())]
+                    };
+                    if hazard.rounds_left > 1 && before[&drifted] != Some(HazardKind::Fire) {
+                        hazards.entry(drifted).or_insert(Hazard {
+                            rounds_left: hazard.rounds_left - 1,
+                            ..hazard
+                        });
+                    }
+                }
+                None => (),
+            }
+        }
+
+        for coords in lava {
+            board.set(coords, TileKind::Lava);
+            hazards.remove(&coords);
+        }
+        self.hazards = hazards;
+
+        let mut report = HazardReport {
+            round: self.round,
+            ..HazardReport::default()
+        };
+        for coords in board.sorted_coordinates() {
+            let now = self.at(board, coords);
+            if now != before[&coords] {
+                report.changed.push((coords, now));
+            }
+        }
+        for (coords, occupant) in board.occupants.iter() {
+            if let Some(kind) = self.at(board, coords) {
+                report.affected.push((occupant, coords, kind));
+            }
+        }
+        report.affected.sort_by_key(|(occupant, _, _)| occupant.id);
+        report
+    }
+
+    /// Draw fire and gas over the tiles. Lava is drawn with the terrain.
+    pub fn draw<T>(&self, canvas: &mut Canvas<T>, layout: &Layout, board: &Board)
+    where
+        T: RenderTarget,
+    {
+        for (coords, hazard) in self.hazards.iter() {
+            if let Some(tile) = board.get(*coords) {
+                tile.mask(canvas, layout, *coords, hazard.kind.color());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::occupant::OccupantKind;
+
+    const MAP: &str = "\
+. . . . . .
+ . . ^ . # .
+. . . . . .
+ . ~ . . . .
+";
+
+    fn run(seed: u64, rounds: u32) -> (Board, Vec<HazardReport>) {
+        let mut board: Board = MAP.parse().unwrap();
+        let mut hazards = HazardSystem::new(seed);
+        assert!(hazards.ignite(&board, Coordinates::from_offset(0, 0), HazardKind::Fire));
+        assert!(hazards.ignite(&board, Coordinates::from_offset(5, 3), HazardKind::Gas));
+        let reports = (0..rounds).map(|_| hazards.step(&mut board)).collect();
+        (board, reports)
+    }
+
+    #[test]
+    fn deterministic() {
+        assert_eq!(run(4, 10), run(4, 10));
+        assert_ne!(run(4, 10).1, run(5, 10).1);
+    }
+
+    #[test]
+    fn spreading() {
+        let (board, reports) = run(7, 30);
+
+        // Lava only ever creeps over floor.
+        assert_eq!(
+            board.get(Coordinates::from_offset(4, 1)).unwrap().kind,
+            TileKind::Rock
+        );
+        assert_eq!(
+            board.get(Coordinates::from_offset(1, 3)).unwrap().kind,
+            TileKind::Water
+        );
+        assert!(reports
+            .iter()
+            .flat_map(|report| report.changed.iter())
+            .any(|(_, hazard)| *hazard == Some(HazardKind::Lava)));
+
+        // Fire and gas die out, lava stays.
+        let last = reports.last().unwrap();
+        assert!(last
+            .changed
+            .iter()
+            .all(|(_, hazard)| *hazard == Some(HazardKind::Lava)));
+        assert_eq!(last.round, 30);
+    }
+
+    #[test]
+    fn rules() {
+        let mut board: Board = MAP.parse().unwrap();
+        let mut hazards = HazardSystem::new(0);
+        let lava = Coordinates::from_offset(2, 1);
+        let fire = Coordinates::from_offset(0, 3);
+
+        assert!(!hazards.ignite(&board, Coordinates::from_offset(4, 1), HazardKind::Fire));
+        assert!(!hazards.ignite(&board, lava, HazardKind::Lava));
+        assert!(hazards.ignite(&board, fire, HazardKind::Fire));
+
+        let dwarf = board.place(fire, OccupantKind::Dwarf).unwrap();
+        let report = hazards.step(&mut board);
+        assert_eq!(report.affected[0], (dwarf, fire, HazardKind::Fire));
+
+        // Fire burns for its whole lifetime, then goes out.
+        for _ in 1..HazardKind::Fire.lifetime().unwrap() {
+            hazards.step(&mut board);
+        }
+        assert_eq!(hazards.get(fire), None);
+    }
+}
@@ -0,0 +1,163 @@
+use super::coordinates::{Coordinates, FloatCoordinates};
+use super::direction::Direction;
+use sdl2::rect::Point;
+
+/// Which way hexes are turned on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// A corner at the top, rows of hexes side by side.
+    Pointy,
+    /// A side at the top, columns of hexes side by side.
+    Flat,
+}
+
+impl Orientation {
+    /// Angle of the first corner, in degrees. Corners go clockwise, 60° apart.
+    fn start_angle(self) -> f64 {
+        match self {
+            Self::Pointy => -30.0,
+            Self::Flat => 0.0,
+        }
+    }
+}
+
+/// How the board is laid out on screen. Every conversion between [Coordinates] and pixels goes
+/// through it, so drawing and mouse picking always agree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub orientation: Orientation,
+    /// Distance from the center of a hex to its corners, in pixels.
+    pub size: f64,
+    /// Pixel position of the center of the hex at q: 0, r: 0.
+    pub origin: Point,
+    /// Distance between hex centers, relative to hexes touching each other. Above 1 leaves a
+    /// gap between hexes.
+    pub spacing: f64,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            orientation: Orientation::Pointy,
+            size: 30.0,
+            origin: Point::new(320, 120),
+            spacing: 1.1,
+        }
+    }
+}
+
+impl Layout {
+    /// Center of a hex, in pixels.
+    pub fn hex_to_pixel(&self, coords: Coordinates) -> Point {
+        let (q, r) = (coords.q as f64, coords.r as f64);
+        let (x, y) = match self.orientation {
+            Orientation::Pointy => (3f64.sqrt() * (q + r / 2.0), 1.5 * r),
+            Orientation::Flat => (1.5 * q, 3f64.sqrt() * (r + q / 2.0)),
+        };
+        let scale = self.size * self.spacing;
+        Point::new(
+            self.origin.x + (x * scale).round() as i32,
+            self.origin.y + (y * scale).round() as i32,
+        )
+    }
+
+    /// Exact position of a pixel, in hexes. Use [Layout::pick] to get the hex under it.
+    pub fn pixel_to_hex(&self, point: Point) -> FloatCoordinates {
+        let scale = self.size * self.spacing;
+        let x = f64::from(point.x - self.origin.x) / scale;
+        let y = f64::from(point.y - self.origin.y) / scale;
+        match self.orientation {
+            Orientation::Pointy => FloatCoordinates {
+                q: x / 3f64.sqrt() - y / 3.0,
+                r: y * 2.0 / 3.0,
+            },
+            Orientation::Flat => FloatCoordinates {
+                q: x * 2.0 / 3.0,
+                r: y / 3f64.sqrt() - x / 3.0,
+            },
+        }
+    }
+
+    /// Hex under a pixel.
+    pub fn pick(&self, point: Point) -> Coordinates {
+        Coordinates::round(self.pixel_to_hex(point))
+    }
+
+    /// Corner `i` of a hex, clockwise from the [orientation's][Orientation] first corner.
+    pub fn corner(&self, coords: Coordinates, i: usize) -> Point {
+        let center = self.hex_to_pixel(coords);
+        let angle_rad = f64::to_radians(self.orientation.start_angle() + 60.0 * i as f64);
+        Point::new(
+            center.x + f64::round(self.size * f64::cos(angle_rad)) as i32,
+            center.y + f64::round(self.size * f64::sin(angle_rad)) as i32,
+        )
+    }
+
+    /// Every corner of a hex, clockwise.
+    pub fn corners(&self, coords: Coordinates) -> [Point; 6] {
+        std::array::from_fn(|i| self.corner(coords, i))
+    }
+
+    /// Both corners of the side of a hex facing `direction`.
+    pub fn side_corners(&self, coords: Coordinates, direction: Direction) -> (Point, Point) {
+        // In both orientations, the side facing TopRight goes from the last corner to the
+        // first, Right from the first to the second, and so on.
+        let index: i32 = direction.into();
+        (
+            self.corner(coords, ((index + 5) % 6) as usize),
+            self.corner(coords, index as usize),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picking() {
+        for orientation in [Orientation::Pointy, Orientation::Flat] {
+            let layout = Layout {
+                orientation,
+                size: 17.0,
+                origin: Point::new(-40, 250),
+                spacing: 1.0,
+            };
+            for coords in Coordinates::from_offset(0, 0).range(6) {
+                let center = layout.hex_to_pixel(coords);
+                assert_eq!(layout.pick(center), coords);
+                // Anywhere inside the hex picks it.
+                for corner in layout.corners(coords) {
+                    let inside = Point::new(
+                        center.x + (corner.x - center.x) * 4 / 5,
+                        center.y + (corner.y - center.y) * 4 / 5,
+                    );
+                    assert_eq!(layout.pick(inside), coords);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shared_sides() {
+        for orientation in [Orientation::Pointy, Orientation::Flat] {
+            let layout = Layout {
+                orientation,
+                spacing: 1.0,
+                ..Layout::default()
+            };
+            let coords = Coordinates { q: 2, r: -1 };
+            for direction in Direction::ALL {
+                let (a, b) = layout.side_corners(coords, direction);
+                let (c, d) = layout.side_corners(coords + direction, direction.opposite());
+                let close = |p: Point, q: Point| (p.x - q.x).abs() <= 1 && (p.y - q.y).abs() <= 1;
+                assert!(
+                    close(a, d) && close(b, c),
+                    "{:?} {:?}",
+                    orientation,
+                    direction
+                );
+            }
+        }
+    }
+}
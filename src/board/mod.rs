@@ -1,18 +1,54 @@
+/// Plain-text map format.
+pub mod ascii;
+/// [Supercover][crate::board::coordinates::Coordinates::supercover] and thick lines, and beams piercing through blockers.
+pub mod beam;
 /// Generate a [Board][crate::board::board::Board], store its [Tiles][crate::board::tile::Tile], and draw it.
 pub mod board;
+/// [Cones][crate::board::cone::Cone] of tiles, for blasts, spreads and sensing.
+pub mod cone;
 /// Lots of maths to translate [Coordinates][crate::board::coordinates::Coordinates] to [Tiles][crate::board::tile::Tile].
 pub mod coordinates;
+/// Graded [Cover][crate::board::cover::Cover] between a shooter and a target.
+pub mod cover;
+/// [Cube][crate::board::cube::Cube] coordinates, and rings, ranges and diagonals around a hex.
+pub mod cube;
 /// [Direction][crate::board::direction::Direction] enum.
 pub mod direction;
+/// [Drilling][crate::board::drill::DrillResult] and blasting through walls.
+pub mod drill;
+/// [Edges][crate::board::edge::Edge] between tiles, and the walls and doors standing on them.
+pub mod edge;
+/// [Events][crate::board::event::BoardEvent] emitted when a [Board][crate::board::board::Board] changes.
+pub mod event;
+/// [Fog of war][crate::board::fog::FogOfWar] for each team.
+pub mod fog;
+/// [Field of view][crate::board::coordinates::Coordinates::field_of_view]: every tile seen from one, in a single sweep.
+pub mod fov;
+/// Pluggable cave [Generators][crate::board::generator::Generator].
+pub mod generator;
+/// Dense [TileGrid][crate::board::grid::TileGrid] storing the tiles of a board.
+pub mod grid;
+/// [HashMap][std::collections::HashMap] storage for the tiles of a board, only there to benchmark against the [grid][crate::board::grid::TileGrid].
+#[cfg(feature = "hashmap-tiles")]
+pub mod hash_grid;
+/// [Hazards][crate::board::hazard::HazardSystem] spreading over the board round after round.
+pub mod hazard;
+/// Screen [Layout][crate::board::layout::Layout]: where hexes are drawn and which one is under the mouse.
+pub mod layout;
+/// Dwarves, enemies, items and objectives standing on the [Board][crate::board::board::Board].
+pub mod occupant;
+/// [Outline][crate::board::outline::Outline] of a [Board][crate::board::board::Board].
+pub mod outline;
+/// Versioned save files for a whole [Board][crate::board::board::Board].
+pub mod save;
+/// [Map sections][crate::board::section::MapSection] stitched together into a cave.
+pub mod section;
 /// [Tile][crate::board::tile::Tile] drawing functions.
 pub mod tile;
+/// [Visibility cache][crate::board::visibility::VisibilityCache], kept up to date with board events.
+pub mod visibility;
+/// Named [Zones][crate::board::zone::Zone] of tiles, like drop-pod zones and extraction areas.
+pub mod zone;
 
 /// [Tile][crate::board::tile::Tile] drawing functions.
 pub mod shape;
-
-/// Size of a tile in pixel.
-const HEX_SIZE: f64 = 30.0;
-/// Window width.
-const WIDTH: u32 = 1280;
-/// Window heigth.
-const HEIGHT: u32 = 600;
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use super::coordinates::Coordinates;
+use sdl2::pixels::Color;
+use serde::{Deserialize, Serialize};
+
+/// What kind of thing occupies a hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OccupantKind {
+    Dwarf,
+    Enemy,
+    /// Something lying on the floor, like a dropped weapon or a resupply pod.
+    Item,
+    Objective,
+}
+
+impl OccupantKind {
+    /// Units are creatures. Only one unit fits in a hex, but it can share it with items and objectives.
+    pub fn is_unit(self) -> bool {
+        matches!(self, Self::Dwarf | Self::Enemy)
+    }
+
+    /// Side the occupant fights for. Items and objectives belong to nobody.
+    pub fn team(self) -> Option<Team> {
+        match self {
+            Self::Dwarf => Some(Team::Dwarves),
+            Self::Enemy => Some(Team::Enemies),
+            Self::Item | Self::Objective => None,
+        }
+    }
+
+    /// Color used to draw the occupant.
+    pub fn color(self) -> Color {
+        match self {
+            Self::Dwarf => Color::RGB(40, 90, 200),
+            Self::Enemy => Color::RGB(200, 40, 40),
+            Self::Item => Color::RGB(220, 180, 40),
+            Self::Objective => Color::RGB(150, 40, 200),
+        }
+    }
+}
+
+/// Side of a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Team {
+    Dwarves,
+    Enemies,
+}
+
+/// Something on the board. The id is unique on its [Board][crate::board::board::Board].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Occupant {
+    pub id: u32,
+    pub kind: OccupantKind,
+}
+
+/// Reasons an occupant could not be placed or moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccupancyError {
+    /// There is no tile at these coordinates.
+    OutOfBoard(Coordinates),
+    /// The tile exists, but nothing can stand on it.
+    NotWalkable(Coordinates),
+    /// Another unit already stands there.
+    Occupied(Coordinates, Occupant),
+    /// No occupant with this id is on the board.
+    Unknown(u32),
+}
+
+impl std::fmt::Display for OccupancyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfBoard(coords) => write!(f, "no tile at {}", coords),
+            Self::NotWalkable(coords) => write!(f, "tile at {} is not walkable", coords),
+            Self::Occupied(coords, occupant) => {
+                write!(f, "tile at {} is occupied by #{}", coords, occupant.id)
+            }
+            Self::Unknown(id) => write!(f, "no occupant #{}", id),
+        }
+    }
+}
+
+impl std::error::Error for OccupancyError {}
+
+/// Keeps track of who stands where, in both directions.
+///
+/// It does not know about terrain, the [Board][crate::board::board::Board] checks it before
+/// changing anything here.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "SavedOccupancy", into = "SavedOccupancy")]
+pub struct Occupancy {
+    hexes: HashMap<Coordinates, Vec<Occupant>>,
+    positions: HashMap<u32, (Coordinates, Occupant)>,
+    next_id: u32,
+}
+
+impl Occupancy {
+    /// Everything on a hex, in placement order.
+    pub fn at(&self, coords: Coordinates) -> &[Occupant] {
+        self.hexes.get(&coords).map_or(&[], |occupants| occupants)
+    }
+
+    /// The unit standing on a hex, if any.
+    pub fn unit_at(&self, coords: Coordinates) -> Option<Occupant> {
+        self.at(coords)
+            .iter()
+            .find(|occupant| occupant.kind.is_unit())
+            .copied()
+    }
+
+    /// Where an occupant is.
+    pub fn position(&self, id: u32) -> Option<Coordinates> {
+        self.positions.get(&id).map(|(coords, _)| *coords)
+    }
+
+    /// Get an occupant from its id.
+    pub fn get(&self, id: u32) -> Option<Occupant> {
+        self.positions.get(&id).map(|(_, occupant)| *occupant)
+    }
+
+    /// Iterate over every occupant and its position.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, Occupant)> + '_ {
+        self.positions.values().copied()
+    }
+
+    /// Put a new occupant on a hex and give it an id.
+    pub fn insert(&mut self, coords: Coordinates, kind: OccupantKind) -> Occupant {
+        let occupant = Occupant {
+            id: self.next_id,
+            kind,
+        };
+        self.next_id += 1;
+        self.hexes.entry(coords).or_default().push(occupant);
+        self.positions.insert(occupant.id, (coords, occupant));
+        occupant
+    }
+
+    /// Take an occupant off the board.
+    pub fn remove(&mut self, id: u32) -> Option<(Coordinates, Occupant)> {
+        let (coords, occupant) = self.positions.remove(&id)?;
+        if let Some(occupants) = self.hexes.get_mut(&coords) {
+            occupants.retain(|other| other.id != id);
+            if occupants.is_empty() {
+                self.hexes.remove(&coords);
+            }
+        }
+        Some((coords, occupant))
+    }
+
+    /// Move an existing occupant to another hex.
+    pub fn relocate(&mut self, id: u32, to: Coordinates) -> Option<Coordinates> {
+        let (from, occupant) = self.remove(id)?;
+        self.hexes.entry(to).or_default().push(occupant);
+        self.positions.insert(id, (to, occupant));
+        Some(from)
+    }
+}
+
+/// Saved form of [Occupancy]: the reverse lookup is rebuilt when loading.
+#[derive(Serialize, Deserialize)]
+struct SavedOccupancy {
+    occupants: Vec<(Coordinates, Occupant)>,
+    next_id: u32,
+}
+
+impl From<Occupancy> for SavedOccupancy {
+    fn from(occupancy: Occupancy) -> Self {
+        let mut occupants: Vec<(Coordinates, Occupant)> = occupancy.iter().collect();
+        occupants.sort_by_key(|(_, occupant)| occupant.id);
+        SavedOccupancy {
+            occupants,
+            next_id: occupancy.next_id,
+        }
+    }
+}
+
+impl From<SavedOccupancy> for Occupancy {
+    fn from(saved: SavedOccupancy) -> Self {
+        let mut occupancy = Occupancy::default();
+        for (coords, occupant) in saved.occupants {
+            occupancy.hexes.entry(coords).or_default().push(occupant);
+            occupancy.positions.insert(occupant.id, (coords, occupant));
+        }
+        occupancy.next_id = saved.next_id;
+        occupancy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::board::Board;
+    use crate::board::outline::Outline;
+
+    fn open_board() -> Board {
+        Board::from_outline(&Outline::Rectangle {
+            width: 5,
+            height: 5,
+        })
+    }
+
+    #[test]
+    fn place_move_remove() {
+        let mut board = open_board();
+        let from = Coordinates::from_offset(1, 1);
+        let to = Coordinates::from_offset(2, 1);
+
+        let dwarf = board.place(from, OccupantKind::Dwarf).unwrap();
+        let item = board.place(from, OccupantKind::Item).unwrap();
+        assert_ne!(dwarf.id, item.id);
+        assert_eq!(board.occupants_at(from), &[dwarf, item]);
+
+        assert_eq!(board.move_occupant(dwarf.id, to), Ok(from));
+        assert_eq!(board.occupants_at(from), &[item]);
+        assert_eq!(board.occupants.position(dwarf.id), Some(to));
+
+        assert_eq!(board.remove_occupant(item.id), Some((from, item)));
+        assert!(board.occupants_at(from).is_empty());
+        assert_eq!(
+            board.move_occupant(item.id, from),
+            Err(OccupancyError::Unknown(item.id))
+        );
+    }
+
+    #[test]
+    fn placement_rules() {
+        let mut board = open_board();
+        let rock = Coordinates::from_offset(3, 3);
+        board.fill(rock);
+        let hex = Coordinates::from_offset(1, 1);
+        let dwarf = board.place(hex, OccupantKind::Dwarf).unwrap();
+
+        assert_eq!(
+            board.place(rock, OccupantKind::Enemy),
+            Err(OccupancyError::NotWalkable(rock))
+        );
+        assert_eq!(
+            board.place(Coordinates { q: 40, r: 40 }, OccupantKind::Enemy),
+            Err(OccupancyError::OutOfBoard(Coordinates { q: 40, r: 40 }))
+        );
+        assert_eq!(
+            board.place(hex, OccupantKind::Enemy),
+            Err(OccupancyError::Occupied(hex, dwarf))
+        );
+        // Items can lie under a unit.
+        assert!(board.place(hex, OccupantKind::Objective).is_ok());
+        // A unit can stay in place.
+        assert_eq!(board.move_occupant(dwarf.id, hex), Ok(hex));
+    }
+
+    #[test]
+    fn enemies_within() {
+        let mut board = open_board();
+        let center = Coordinates::from_offset(2, 2);
+        let near = board
+            .place(Coordinates::from_offset(3, 2), OccupantKind::Enemy)
+            .unwrap();
+        board
+            .place(Coordinates::from_offset(4, 4), OccupantKind::Enemy)
+            .unwrap();
+        board
+            .place(Coordinates::from_offset(2, 1), OccupantKind::Dwarf)
+            .unwrap();
+
+        assert_eq!(
+            board.enemies_within(center, 1),
+            vec![(Coordinates::from_offset(3, 2), near)]
+        );
+        assert_eq!(board.enemies_within(center, 3).len(), 2);
+        assert_eq!(board.occupants_within(center, 3).len(), 3);
+    }
+
+    #[test]
+    fn occupants_block_paths_and_sight() {
+        let mut board = open_board();
+        for y in 0..5 {
+            board.fill(Coordinates::from_offset(2, y));
+        }
+        let gap = Coordinates::from_offset(2, 2);
+        board.free(gap);
+        let from = Coordinates::from_offset(0, 2);
+        let to = Coordinates::from_offset(4, 2);
+        board.place(gap, OccupantKind::Enemy).unwrap();
+
+        assert!(board.path(from, to).is_some());
+        assert!(board
+            .path_with(from, to, |occupant| occupant.kind == OccupantKind::Enemy)
+            .is_none());
+        assert!(board
+            .path_with(from, to, |occupant| occupant.kind == OccupantKind::Dwarf)
+            .is_some());
+
+        // The only way through the wall is the gap the enemy stands in.
+        assert!(board.line_of_sight(from, to, |_| false));
+        assert!(!board.line_of_sight(from, to, |occupant| occupant.kind.is_unit()));
+    }
+}
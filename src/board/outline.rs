@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+
+use super::coordinates::Coordinates;
+use serde::{Deserialize, Serialize};
+
+/// Which tiles a [Board][crate::board::board::Board] is made of.
+///
+/// Except for [Mask][Outline::Mask], outlines are laid out so that every tile has positive
+/// offset coordinates, which keeps them on screen and writable as [text][crate::board::ascii].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Outline {
+    /// `height` rows of `width` tiles.
+    Rectangle { width: i32, height: i32 },
+    /// Every tile at most `radius` tiles away from the center tile.
+    Hexagon { radius: u32 },
+    /// `width` tiles along the q axis, `height` along the r axis.
+    Parallelogram { width: i32, height: i32 },
+    /// Exactly these tiles.
+    Mask(Vec<Coordinates>),
+}
+
+impl Outline {
+    /// The physical 15x15 board: odd rows are one tile shorter, and rows 4 and 10 have a notch
+    /// on each side.
+    pub fn canonical() -> Self {
+        let tiles = Outline::Rectangle {
+            width: 15,
+            height: 15,
+        }
+        .coordinates()
+        .into_iter()
+        .filter(|coords| {
+            let (x, y) = coords.to_offset();
+            // remove last piece from every odd row to get nice square board.
+            let short_row = x == 14 && y & 1 != 0;
+            // Remove pieces to be iso with the canonical board.
+            let notch = (x == 0 || x == 14) && (y == 4 || y == 10);
+            !short_row && !notch
+        })
+        .collect();
+
+        Outline::Mask(tiles)
+    }
+
+    /// Every tile of the outline, row by row.
+    pub fn coordinates(&self) -> Vec<Coordinates> {
+        let mut tiles: Vec<Coordinates> = match self {
+            Self::Rectangle { width, height } => (0..*height)
+                .flat_map(|y| (0..*width).map(move |x| Coordinates::from_offset(x, y)))
+                .collect(),
+            Self::Hexagon { radius } => {
+                let tiles = Coordinates { q: 0, r: 0 }.range(*radius).collect();
+                to_positive_offsets(tiles)
+            }
+            Self::Parallelogram { width, height } => (0..*height)
+                .flat_map(|r| (0..*width).map(move |q| Coordinates { q, r }))
+                .collect(),
+            Self::Mask(tiles) => {
+                let mut seen: HashSet<Coordinates> = HashSet::new();
+                tiles
+                    .iter()
+                    .filter(|coords| seen.insert(**coords))
+                    .copied()
+                    .collect()
+            }
+        };
+
+        tiles.sort_by_key(|coords| (coords.r, coords.q));
+        tiles
+    }
+}
+
+/// Move tiles so the top row is row 0 and the leftmost tile is in column 0.
+fn to_positive_offsets(tiles: Vec<Coordinates>) -> Vec<Coordinates> {
+    let top = tiles.iter().map(|coords| coords.r).min().unwrap_or(0);
+    let tiles: Vec<Coordinates> = tiles
+        .into_iter()
+        .map(|coords| coords - Coordinates { q: 0, r: top })
+        .collect();
+    let left = tiles
+        .iter()
+        .map(|coords| coords.to_offset().0)
+        .min()
+        .unwrap_or(0);
+
+    tiles
+        .into_iter()
+        .map(|coords| coords - Coordinates { q: left, r: 0 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(
+            Outline::Rectangle {
+                width: 7,
+                height: 4
+            }
+            .coordinates()
+            .len(),
+            28
+        );
+        assert_eq!(Outline::Hexagon { radius: 0 }.coordinates().len(), 1);
+        assert_eq!(Outline::Hexagon { radius: 3 }.coordinates().len(), 37);
+        assert_eq!(
+            Outline::Parallelogram {
+                width: 3,
+                height: 5
+            }
+            .coordinates()
+            .len(),
+            15
+        );
+        // 8 rows of 15 tiles, 7 rows of 14 tiles, minus 4 notches.
+        assert_eq!(
+            Outline::canonical().coordinates().len(),
+            8 * 15 + 7 * 14 - 4
+        );
+        assert_eq!(
+            Outline::Mask(vec![Coordinates { q: 1, r: 1 }, Coordinates { q: 1, r: 1 }])
+                .coordinates()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn positive_offsets() {
+        for outline in [
+            Outline::Hexagon { radius: 1 },
+            Outline::Hexagon { radius: 6 },
+            Outline::Parallelogram {
+                width: 9,
+                height: 9,
+            },
+            Outline::canonical(),
+        ] {
+            let offsets: Vec<(i32, i32)> = outline
+                .coordinates()
+                .iter()
+                .map(|coords| coords.to_offset())
+                .collect();
+            assert_eq!(offsets.iter().map(|(x, _)| *x).min(), Some(0));
+            assert_eq!(offsets.iter().map(|(_, y)| *y).min(), Some(0));
+        }
+    }
+
+    #[test]
+    fn hexagon_is_round() {
+        let tiles = Outline::Hexagon { radius: 4 }.coordinates();
+        let center = tiles
+            .iter()
+            .find(|center| {
+                tiles.iter().all(|coords| {
+                    let (q, r) = (coords.q - center.q, coords.r - center.r);
+                    q.abs().max(r.abs()).max((q + r).abs()) <= 4
+                })
+            })
+            .expect("hexagon should have a center");
+
+        assert_eq!(center.to_offset(), (4, 4));
+    }
+}
@@ -0,0 +1,236 @@
+use std::io::{Read, Write};
+
+use super::board::Board;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Version written in new saves. Bump it and add a step to [migrate] whenever an existing field
+/// changes meaning; new fields only need a serde default.
+pub const VERSION: u32 = 1;
+
+/// Reasons a save could not be written or read.
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    /// The save isn't valid JSON, or doesn't describe a board.
+    Format(serde_json::Error),
+    /// The save was written by a newer version of the game.
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not access save: {}", error),
+            Self::Format(error) => write!(f, "invalid save: {}", error),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "save version {} is newer than supported version {}",
+                version, VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Format(error)
+    }
+}
+
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: u32,
+    board: &'a Board,
+}
+
+#[derive(Deserialize)]
+struct RawSaveFile {
+    version: u32,
+    board: Value,
+}
+
+/// Write the whole board state as JSON, with a version header.
+pub fn write<W: Write>(board: &Board, writer: W) -> Result<(), SaveError> {
+    let file = SaveFile {
+        version: VERSION,
+        board,
+    };
+    serde_json::to_writer_pretty(writer, &file)?;
+    Ok(())
+}
+
+/// Read a board written by [write], by this version of the game or an older one.
+pub fn read<R: Read>(reader: R) -> Result<Board, SaveError> {
+    let file: RawSaveFile = serde_json::from_reader(reader)?;
+    if file.version > VERSION {
+        return Err(SaveError::UnsupportedVersion(file.version));
+    }
+
+    let board = migrate(file.version, file.board);
+    Ok(serde_json::from_value(board)?)
+}
+
+/// Bring a board saved with an older version up to date, one version at a time.
+fn migrate(_version: u32, board: Value) -> Value {
+    // Version 1 is the first one, and still the current one: nothing to do yet.
+    board
+}
+
+/// Serialize maps keyed by [Coordinates][super::coordinates::Coordinates] as a list of pairs,
+/// since JSON keys can only be strings. Pairs are sorted row by row so saves are stable.
+pub mod by_coordinates {
+    use std::collections::HashMap;
+
+    use crate::board::coordinates::Coordinates;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<V, S>(map: &HashMap<Coordinates, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        V: Serialize,
+        S: Serializer,
+    {
+        let mut pairs: Vec<(&Coordinates, &V)> = map.iter().collect();
+        pairs.sort_by_key(|(coords, _)| (coords.r, coords.q));
+        serializer.collect_seq(pairs)
+    }
+
+    pub fn deserialize<'de, V, D>(deserializer: D) -> Result<HashMap<Coordinates, V>, D::Error>
+    where
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let pairs: Vec<(Coordinates, V)> = Vec::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+/// Serialize maps keyed by [Edge][super::edge::Edge] as a list of pairs, sorted like
+/// [by_coordinates].
+pub mod by_edge {
+    use std::collections::HashMap;
+
+    use crate::board::edge::Edge;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<V, S>(map: &HashMap<Edge, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        V: Serialize,
+        S: Serializer,
+    {
+        let mut pairs: Vec<(&Edge, &V)> = map.iter().collect();
+        pairs.sort_by_key(|(edge, _)| {
+            let coords = edge.coordinates();
+            let direction: i32 = edge.direction().into();
+            (coords.r, coords.q, direction)
+        });
+        serializer.collect_seq(pairs)
+    }
+
+    pub fn deserialize<'de, V, D>(deserializer: D) -> Result<HashMap<Edge, V>, D::Error>
+    where
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let pairs: Vec<(Edge, V)> = Vec::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::board::BoardConfig;
+    use crate::board::coordinates::Coordinates;
+    use crate::board::direction::Direction;
+    use crate::board::edge::EdgeKind;
+    use crate::board::generator::CellularAutomata;
+    use crate::board::occupant::OccupantKind;
+    use crate::board::shape::Shape;
+    use crate::board::tile::{Tile, TileKind};
+    use crate::board::zone::{Zone, ZoneRole};
+
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: Serialize + for<'de> Deserialize<'de>,
+    {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let coords = Coordinates { q: -3, r: 7 };
+        assert_eq!(round_trip(&coords), coords);
+
+        for kind in TileKind::ALL {
+            assert_eq!(round_trip(&Tile::new(kind)), Tile::new(kind));
+        }
+
+        for direction in (0..6).map(Direction::from) {
+            assert_eq!(round_trip(&direction), direction);
+        }
+
+        let shape = Shape {
+            center: Coordinates { q: 2, r: -1 },
+            tiles: vec![Coordinates { q: 1, r: -1 }, Coordinates { q: 0, r: 0 }],
+        };
+        assert_eq!(round_trip(&shape), shape);
+    }
+
+    #[test]
+    fn board_round_trip() {
+        let mut board = Board::generate(5, &BoardConfig::default(), &CellularAutomata::default());
+        let walkable: Vec<Coordinates> = board
+            .sorted_coordinates()
+            .into_iter()
+            .filter(|coords| board.tiles[coords].kind.is_walkable())
+            .collect();
+        let dwarf = board.place(walkable[0], OccupantKind::Dwarf).unwrap();
+        board.place(walkable[1], OccupantKind::Enemy).unwrap();
+        board.place(walkable[1], OccupantKind::Item).unwrap();
+        board.remove_occupant(dwarf.id);
+        board.spawns.insert(walkable[2], OccupantKind::Dwarf);
+        board.set(walkable[3], TileKind::Water);
+        board.set_height(walkable[3], -1);
+        board.define_zone(Zone::new("exit", ZoneRole::Extraction, &walkable[4..7]));
+        board.set_edge(walkable[7], Direction::Left, Some(EdgeKind::ClosedDoor));
+
+        let mut saved: Vec<u8> = vec![];
+        write(&board, &mut saved).unwrap();
+        let loaded = read(saved.as_slice()).unwrap();
+
+        assert_eq!(loaded, board);
+        // Ids of removed occupants are not handed out again.
+        assert_eq!(
+            board.clone().place(walkable[0], OccupantKind::Dwarf),
+            loaded.clone().place(walkable[0], OccupantKind::Dwarf)
+        );
+    }
+
+    #[test]
+    fn versions() {
+        let old = r#"{
+            "version": 1,
+            "board": {"tiles": [[{"q": 0, "r": 0}, {"kind": "Floor"}]]}
+        }"#;
+        let board = read(old.as_bytes()).unwrap();
+        assert_eq!(board.tiles.keys().count(), 1);
+        assert!(board.spawns.is_empty());
+
+        let new = r#"{"version": 1000, "board": {}}"#;
+        assert!(matches!(
+            read(new.as_bytes()),
+            Err(SaveError::UnsupportedVersion(1000))
+        ));
+
+        assert!(matches!(read("{}".as_bytes()), Err(SaveError::Format(_))));
+    }
+}
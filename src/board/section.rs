@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use super::ascii::ParseError;
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::tile::{Tile, TileKind};
+use serde::{Deserialize, Serialize};
+
+/// Both sides of the two standard sections, written as [text][crate::board::ascii].
+const STANDARD: [(&str, &str); 4] = [
+    (
+        "1A",
+        "  . . .
+ . # . .
+. . . . .
+ . . # .
+  . . .",
+    ),
+    (
+        "1B",
+        "  . . .
+ . ~ ~ .
+. ~ ~ ~ .
+ . ~ ~ .
+  . . .",
+    ),
+    (
+        "2A",
+        "  # # .
+ # $ . .
+# $ . . .
+ # . . .
+  . . .",
+    ),
+    (
+        "2B",
+        "  . . .
+ . ^ . .
+. . ^ ^ .
+ . . . ^
+  . . .",
+    ),
+];
+
+/// A piece of cave, like the double-sided map sections the physical game is built from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapSection {
+    /// Name of the section and its side, like "1A".
+    pub id: String,
+    /// Terrain of every tile, relative to the section's anchor.
+    pub tiles: Vec<(Coordinates, TileKind)>,
+}
+
+impl MapSection {
+    /// Read a section written as [text][crate::board::ascii].
+    ///
+    /// The anchor is the middle tile of the text: the middle column of the middle row.
+    pub fn from_text(id: &str, text: &str) -> Result<Self, ParseError> {
+        let board: Board = text.parse()?;
+        let offsets: Vec<(i32, i32)> = board
+            .tiles
+            .keys()
+            .map(|coords| coords.to_offset())
+            .collect();
+        let middle = |values: Vec<i32>| {
+            (values.iter().min().unwrap_or(&0) + values.iter().max().unwrap_or(&0)) / 2
+        };
+        let y = middle(offsets.iter().map(|(_, y)| *y).collect());
+        let x = middle(
+            offsets
+                .iter()
+                .filter(|(_, row)| *row == y)
+                .map(|(x, _)| *x)
+                .collect(),
+        );
+        let anchor = Coordinates::from_offset(x, y);
+
+        let mut tiles: Vec<(Coordinates, TileKind)> = board
+            .tiles
+            .iter()
+            .map(|(coords, tile)| (coords - anchor, tile.kind))
+            .collect();
+        tiles.sort_by_key(|(coords, _)| (coords.r, coords.q));
+
+        Ok(MapSection {
+            id: id.to_string(),
+            tiles,
+        })
+    }
+
+    /// Rotate the section by 60° clockwise around its anchor.
+    pub fn rotate_clockwise(&mut self) {
+        for (coords, _) in self.tiles.iter_mut() {
+            *coords = coords.rotate_clockwise();
+        }
+    }
+
+    /// Rotate the section by 60° counterclockwise around its anchor.
+    pub fn rotate_counterclockwise(&mut self) {
+        for (coords, _) in self.tiles.iter_mut() {
+            *coords = coords.rotate_counterclockwise();
+        }
+    }
+
+    /// Copy of the section, rotated by `turns` sixths of a turn. Negative turns go counterclockwise.
+    pub fn rotated(&self, turns: i32) -> Self {
+        let mut section = self.clone();
+        for _ in 0..turns.rem_euclid(6) {
+            section.rotate_clockwise();
+        }
+        section
+    }
+}
+
+/// Why sections could not be put together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionError {
+    /// No section with this id in the library.
+    Unknown(String),
+    /// A tile of the section would land on an existing tile.
+    Overlap(Coordinates),
+}
+
+impl std::fmt::Display for SectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(id) => write!(f, "unknown section {}", id),
+            Self::Overlap(coords) => write!(f, "sections overlap at {}", coords),
+        }
+    }
+}
+
+impl std::error::Error for SectionError {}
+
+/// Where and how to lay a section when assembling a map.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Placement {
+    /// Id of the section in the [SectionLibrary].
+    pub id: String,
+    /// Where the section's anchor goes on the board.
+    pub anchor: Coordinates,
+    /// Clockwise sixths of a turn, see [MapSection::rotated].
+    pub turns: i32,
+}
+
+/// Every section that can be used to assemble a map, by id.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SectionLibrary {
+    pub sections: HashMap<String, MapSection>,
+}
+
+impl SectionLibrary {
+    /// Library with both sides of the standard sections.
+    pub fn standard() -> Self {
+        let mut library = SectionLibrary::default();
+        for (id, text) in STANDARD {
+            library.add(MapSection::from_text(id, text).expect("standard sections should parse"));
+        }
+        library
+    }
+
+    /// Add a section to the library, replacing any section with the same id.
+    pub fn add(&mut self, section: MapSection) {
+        self.sections.insert(section.id.clone(), section);
+    }
+
+    /// Build a map by stitching sections one after the other.
+    pub fn assemble(&self, placements: &[Placement]) -> Result<Board, SectionError> {
+        let mut board: Board = Board::default();
+        for placement in placements {
+            let section = self
+                .sections
+                .get(&placement.id)
+                .ok_or_else(|| SectionError::Unknown(placement.id.clone()))?;
+            board.stitch(&section.rotated(placement.turns), placement.anchor)?;
+        }
+        // A brand new map isn't news to anyone.
+        board.events.clear();
+        Ok(board)
+    }
+}
+
+impl Board {
+    /// Add the tiles of a section to the board, with the section's anchor at `anchor`.
+    ///
+    /// Nothing is changed if any tile would land on an existing one. Off the board is solid rock,
+    /// so each new tile made of something else emits
+    /// [TileChanged][super::event::BoardEvent::TileChanged] from rock.
+    pub fn stitch(
+        &mut self,
+        section: &MapSection,
+        anchor: Coordinates,
+    ) -> Result<(), SectionError> {
+        if let Some((coords, _)) = section
+            .tiles
+            .iter()
+            .find(|(coords, _)| self.get(*coords + anchor).is_some())
+        {
+            return Err(SectionError::Overlap(*coords + anchor));
+        }
+
+        for (coords, kind) in section.tiles.iter() {
+            self.tiles
+                .insert(*coords + anchor, Tile::new(TileKind::Rock));
+            self.set(*coords + anchor, *kind);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::event::BoardEvent;
+
+    #[test]
+    fn from_text() {
+        let library = SectionLibrary::standard();
+        let section = &library.sections["1A"];
+
+        assert_eq!(section.tiles.len(), 19);
+        // Anchor is the center of the hexagon.
+        assert!(section.tiles.iter().all(|(coords, _)| coords
+            .q
+            .abs()
+            .max(coords.r.abs())
+            .max((coords.q + coords.r).abs())
+            <= 2));
+    }
+
+    #[test]
+    fn rotation() {
+        let section = SectionLibrary::standard().sections["2B"].clone();
+
+        assert_eq!(section.rotated(6), section);
+        assert_eq!(section.rotated(-1), section.rotated(5));
+        assert_ne!(section.rotated(1), section);
+
+        let mut manual = section.clone();
+        manual.rotate_counterclockwise();
+        manual.rotate_counterclockwise();
+        assert_eq!(manual, section.rotated(-2));
+
+        // Same rotation as shapes.
+        let lava = Coordinates { q: 0, r: -1 };
+        assert_eq!(
+            section.tiles.iter().find(|(c, _)| *c == lava).unwrap().1,
+            TileKind::Lava
+        );
+        let rotated = section.rotated(1);
+        assert_eq!(
+            rotated
+                .tiles
+                .iter()
+                .find(|(c, _)| *c == lava.rotate_clockwise())
+                .unwrap()
+                .1,
+            TileKind::Lava
+        );
+    }
+
+    #[test]
+    fn assemble() {
+        let library = SectionLibrary::standard();
+        let placement = |id: &str, q, r, turns| Placement {
+            id: id.to_string(),
+            anchor: Coordinates { q, r },
+            turns,
+        };
+
+        let board = library
+            .assemble(&[
+                placement("1A", 2, 2, 0),
+                placement("2B", 7, 0, 3),
+                placement("1B", 4, 5, 1),
+            ])
+            .unwrap();
+        assert_eq!(board.tiles.keys().count(), 3 * 19);
+        assert!(board.events.pending().is_empty());
+        assert_eq!(
+            board.get(Coordinates { q: 4, r: 5 }).unwrap().kind,
+            TileKind::Water
+        );
+
+        assert_eq!(
+            library.assemble(&[placement("1A", 0, 0, 0), placement("1B", 1, 0, 0)]),
+            Err(SectionError::Overlap(Coordinates { q: 1, r: -2 }))
+        );
+        assert_eq!(
+            library.assemble(&[placement("9Z", 0, 0, 0)]),
+            Err(SectionError::Unknown("9Z".to_string()))
+        );
+    }
+
+    #[test]
+    fn stitch_events() {
+        let library = SectionLibrary::standard();
+        let section = &library.sections["1A"];
+        let mut board = Board::default();
+        let anchor = Coordinates { q: 3, r: 1 };
+        board.stitch(section, anchor).unwrap();
+
+        let events = board.drain_events();
+        let expected = section
+            .tiles
+            .iter()
+            .filter(|(_, kind)| *kind != TileKind::Rock)
+            .count();
+        assert_eq!(events.len(), expected);
+        for event in events {
+            let BoardEvent::TileChanged { coords, old, new } = event else {
+                panic!("unexpected event {:?}", event);
+            };
+            assert_eq!(old, TileKind::Rock);
+            assert_eq!(board.get(coords).unwrap().kind, new);
+        }
+    }
+}
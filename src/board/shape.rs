@@ -1,6 +1,7 @@
 use super::coordinates::Coordinates;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Shape {
     /// Center of the shape.
     pub center: Coordinates,
@@ -13,19 +14,13 @@ pub struct Shape {
 impl Shape {
     pub fn rotate_clockwise(&mut self) {
         for tile in self.tiles.iter_mut() {
-            let (q, r, s) = (tile.q, tile.r, -tile.q - tile.r);
-            let vec = (-r, -s, -q);
-            (*tile).q = vec.0;
-            (*tile).r = vec.1;
+            *tile = tile.rotate_clockwise();
         }
     }
 
     pub fn rotate_counterclockwise(&mut self) {
         for tile in self.tiles.iter_mut() {
-            let (q, r, s) = (tile.q, tile.r, -tile.q - tile.r);
-            let vec = (-s, -q, -r);
-            (*tile).q = vec.0;
-            (*tile).r = vec.1;
+            *tile = tile.rotate_counterclockwise();
         }
     }
 }
@@ -2,33 +2,143 @@ use sdl2::gfx::primitives::{DrawRenderer, ToColor};
 use sdl2::pixels::Color;
 use sdl2::rect::Point;
 use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
 
-use super::HEX_SIZE;
-use crate::Coordinates;
+use super::layout::Layout;
+use crate::board::coordinates::Coordinates;
 
-#[derive(Debug, Clone, Copy)]
+/// What a [Tile] is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TileKind {
+    /// Open cave floor.
+    Floor,
+    /// Loose dirt wall.
+    Dirt,
+    /// Solid rock wall.
+    Rock,
+    /// Gold vein, embedded in a wall.
+    Mineral,
+    /// Nitra vein, embedded in a wall.
+    Nitra,
+    /// Molten rock. Nobody walks on it twice.
+    Lava,
+    /// Shallow water, slows dwarves down.
+    Water,
+    /// Hole in the floor.
+    Pit,
+}
+
+impl TileKind {
+    /// Every kind, in declaration order.
+    pub const ALL: [TileKind; 8] = [
+        Self::Floor,
+        Self::Dirt,
+        Self::Rock,
+        Self::Mineral,
+        Self::Nitra,
+        Self::Lava,
+        Self::Water,
+        Self::Pit,
+    ];
+
+    /// Can a creature stand on it.
+    pub fn is_walkable(self) -> bool {
+        matches!(self, Self::Floor | Self::Water)
+    }
+
+    /// Does it block line of sight.
+    pub fn is_opaque(self) -> bool {
+        matches!(self, Self::Dirt | Self::Rock | Self::Mineral | Self::Nitra)
+    }
+
+    /// Can fire burn on it.
+    pub fn is_flammable(self) -> bool {
+        self == Self::Floor
+    }
+
+    /// Movement points needed to enter the tile.
+    /// Only meaningful if the tile [is walkable][TileKind::is_walkable].
+    pub fn movement_cost(self) -> i32 {
+        match self {
+            Self::Water => 2,
+            _ => 1,
+        }
+    }
+
+    /// Drill power needed to dig through it. None if there is nothing to dig.
+    pub fn hardness(self) -> Option<u32> {
+        match self {
+            Self::Dirt => Some(1),
+            Self::Rock | Self::Nitra => Some(2),
+            Self::Mineral => Some(3),
+            Self::Floor | Self::Lava | Self::Water | Self::Pit => None,
+        }
+    }
+
+    /// Action points spent digging through it.
+    /// Only meaningful if the tile has a [hardness][TileKind::hardness].
+    pub fn drill_cost(self) -> u32 {
+        match self {
+            Self::Dirt => 1,
+            Self::Rock => 2,
+            Self::Nitra | Self::Mineral => 3,
+            _ => 0,
+        }
+    }
+
+    /// Base color used to draw the tile.
+    pub fn color(self) -> Color {
+        match self {
+            Self::Floor => Color::RGB(200, 200, 200),
+            Self::Dirt => Color::RGB(150, 120, 90),
+            Self::Rock => Color::RGB(170, 170, 170),
+            Self::Mineral => Color::RGB(212, 175, 55),
+            Self::Nitra => Color::RGB(200, 60, 60),
+            Self::Lava => Color::RGB(230, 90, 20),
+            Self::Water => Color::RGB(70, 130, 200),
+            Self::Pit => Color::RGB(40, 40, 40),
+        }
+    }
+}
+
+/// Highest difference in height a creature can walk up or down without a climbing tool.
+pub const MAX_STEP: i32 = 1;
+/// Extra movement points needed for each level climbed up.
+pub const CLIMB_COST: i32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Tile {
-    pub free: bool,
+    pub kind: TileKind,
+    /// Height of the ground, in levels. Walls stand one level above their ground.
+    #[serde(default)]
+    pub height: i32,
 }
 
 impl Tile {
-    pub fn new(free: bool) -> Self {
-        Tile { free: free }
+    pub fn new(kind: TileKind) -> Self {
+        Tile { kind, height: 0 }
+    }
+
+    /// Highest point of the tile: the top of the wall if there is one.
+    pub fn top(&self) -> i32 {
+        if self.kind.is_opaque() {
+            self.height + 1
+        } else {
+            self.height
+        }
     }
 
     /// Apply color mask on a tile.
-    pub fn add_color<T>(&self, canvas: &mut Canvas<T>, coordinates: Coordinates, color: Color)
-    where
+    pub fn add_color<T>(
+        &self,
+        canvas: &mut Canvas<T>,
+        layout: &Layout,
+        coordinates: Coordinates,
+        color: Color,
+    ) where
         T: RenderTarget,
     {
-        let orig: Point = coordinates.into();
-        let mut points: [Point; 6] = [Point::new(0, 0); 6];
-        for i in 0..6 {
-            let angle_deg: f64 = (60 * (i as i32) - 30) as f64;
-            let angle_rad: f64 = f64::to_radians(angle_deg);
-            points[i].x = orig.x + (f64::round(HEX_SIZE * f64::cos(angle_rad)) as i32);
-            points[i].y = orig.y + (f64::round(HEX_SIZE * f64::sin(angle_rad)) as i32);
-        }
+        let points: [Point; 6] = layout.corners(coordinates);
         canvas.set_draw_color(Color::RGB(0, 20, 0));
 
         canvas
@@ -52,24 +162,20 @@ impl Tile {
     }
 
     /// Draw a tile with given color and add anti-aliased borders.
-    pub fn draw<T>(&self, canvas: &mut Canvas<T>, coordinates: Coordinates, debug: bool)
-    where
+    pub fn draw<T>(
+        &self,
+        canvas: &mut Canvas<T>,
+        layout: &Layout,
+        coordinates: Coordinates,
+        debug: bool,
+    ) where
         T: RenderTarget,
     {
-        let orig: Point = coordinates.into();
-        let mut points: [Point; 6] = [Point::new(0, 0); 6];
-        for i in 0..6 {
-            let angle_deg: f64 = (60 * (i as i32) - 30) as f64;
-            let angle_rad: f64 = f64::to_radians(angle_deg);
-            points[i].x = orig.x + (f64::round(HEX_SIZE * f64::cos(angle_rad)) as i32);
-            points[i].y = orig.y + (f64::round(HEX_SIZE * f64::sin(angle_rad)) as i32);
-        }
+        let orig: Point = layout.hex_to_pixel(coordinates);
+        let points: [Point; 6] = layout.corners(coordinates);
         canvas.set_draw_color(Color::RGB(0, 20, 0));
 
-        let mut color: Color = Color::RGB(170, 170, 170);
-        if self.free {
-            color = Color::RGB(200, 200, 200)
-        }
+        let color: Color = self.kind.color();
 
         canvas
             .filled_polygon(
@@ -107,22 +213,30 @@ impl Tile {
                 )
                 .unwrap();
         }
+        if self.height != 0 {
+            canvas
+                .string(
+                    (orig.x - 8) as i16,
+                    (orig.y + 10) as i16,
+                    &format!("{:+}", self.height),
+                    Color::RGB(0, 20, 0),
+                )
+                .unwrap();
+        }
     }
 
     /// Apply a mask on a color
-    pub fn mask<T, C>(&self, canvas: &mut Canvas<T>, coordinates: Coordinates, color: C)
-    where
+    pub fn mask<T, C>(
+        &self,
+        canvas: &mut Canvas<T>,
+        layout: &Layout,
+        coordinates: Coordinates,
+        color: C,
+    ) where
         T: RenderTarget,
         C: ToColor,
     {
-        let orig: Point = coordinates.into();
-        let mut points: [Point; 6] = [Point::new(0, 0); 6];
-        for i in 0..6 {
-            let angle_deg: f64 = (60 * (i as i32) - 30) as f64;
-            let angle_rad: f64 = f64::to_radians(angle_deg);
-            points[i].x = orig.x + (f64::round(HEX_SIZE * f64::cos(angle_rad)) as i32);
-            points[i].y = orig.y + (f64::round(HEX_SIZE * f64::sin(angle_rad)) as i32);
-        }
+        let points: [Point; 6] = layout.corners(coordinates);
 
         canvas
             .filled_polygon(
@@ -0,0 +1,272 @@
+use std::collections::{HashMap, HashSet};
+
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::edge::EdgeKind;
+use super::event::BoardEvent;
+use super::occupant::Occupant;
+
+/// How well a [VisibilityCache] does.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Questions answered from the cache.
+    pub hits: u64,
+    /// Questions that had to be computed.
+    pub misses: u64,
+    /// Answers thrown away because the board changed.
+    pub invalidated: u64,
+}
+
+impl CacheStats {
+    /// Share of the questions answered from the cache, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+/// Remembers who can see what, see [Board::line_of_sight], until the board changes.
+///
+/// Pass every [BoardEvent] to [VisibilityCache::handle]: only the answers a change may affect
+/// are thrown away.
+#[derive(Debug, Clone)]
+pub struct VisibilityCache {
+    /// Occupants blocking sight.
+    blocks: fn(&Occupant) -> bool,
+    /// Known answers, by origin then target.
+    sight: HashMap<Coordinates, HashMap<Coordinates, bool>>,
+    /// Origins whose answers are known for every tile of the board.
+    complete: HashSet<Coordinates>,
+    stats: CacheStats,
+}
+
+impl VisibilityCache {
+    /// Empty cache, where occupants for which `blocks` is true block sight.
+    pub fn new(blocks: fn(&Occupant) -> bool) -> Self {
+        VisibilityCache {
+            blocks,
+            sight: HashMap::new(),
+            complete: HashSet::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Number of answers known.
+    fn len(&self) -> usize {
+        self.sight.values().map(HashMap::len).sum()
+    }
+
+    /// Forget everything.
+    pub fn clear(&mut self) {
+        self.stats.invalidated += self.len() as u64;
+        self.sight.clear();
+        self.complete.clear();
+    }
+
+    fn known(&self, from: Coordinates, to: Coordinates) -> Option<bool> {
+        // Sight goes both ways.
+        let answer = |a, b| {
+            self.sight
+                .get(&a)
+                .and_then(|targets| targets.get(&b))
+                .copied()
+        };
+        answer(from, to).or_else(|| answer(to, from))
+    }
+
+    /// Same as [Board::line_of_sight].
+    pub fn line_of_sight(&mut self, board: &Board, from: Coordinates, to: Coordinates) -> bool {
+        if let Some(visible) = self.known(from, to) {
+            self.stats.hits += 1;
+            return visible;
+        }
+        self.stats.misses += 1;
+        let visible = board.line_of_sight(from, to, self.blocks);
+        self.sight.entry(from).or_default().insert(to, visible);
+        visible
+    }
+
+    /// Every tile of the board `from` can see, see [Board::field_of_view].
+    pub fn visible_from(&mut self, board: &Board, from: Coordinates) -> HashSet<Coordinates> {
+        if self.complete.contains(&from) {
+            self.stats.hits += 1;
+            return self.sight[&from]
+                .iter()
+                .filter(|(_, visible)| **visible)
+                .map(|(coords, _)| *coords)
+                .collect();
+        }
+        self.stats.misses += 1;
+        let seen = board.field_of_view(from, None, self.blocks);
+        let targets = self.sight.entry(from).or_default();
+        for (coords, _) in board.iter() {
+            targets.insert(coords, seen.contains(&coords));
+        }
+        self.complete.insert(from);
+        seen
+    }
+
+    /// Throw away every answer the events may change.
+    /// Returns the number of answers thrown away.
+    pub fn handle(&mut self, events: &[BoardEvent]) -> usize {
+        let mut changed: Vec<Coordinates> = vec![];
+        for event in events {
+            let relevant = match event {
+                BoardEvent::TileChanged { old, new, .. } => old.is_opaque() != new.is_opaque(),
+                BoardEvent::HeightChanged { .. } => true,
+                BoardEvent::EdgeChanged { old, new, .. } => {
+                    old.is_some_and(EdgeKind::blocks_sight)
+                        != new.is_some_and(EdgeKind::blocks_sight)
+                }
+                BoardEvent::OccupantPlaced { occupant, .. }
+                | BoardEvent::OccupantMoved { occupant, .. }
+                | BoardEvent::OccupantRemoved { occupant, .. } => (self.blocks)(occupant),
+            };
+            if relevant {
+                changed.extend(event.coordinates());
+            }
+        }
+        if changed.is_empty() {
+            return 0;
+        }
+
+        let mut invalidated = 0;
+        for (from, targets) in self.sight.iter_mut() {
+            let before = targets.len();
+            targets.retain(|to, _| !changed.iter().any(|coords| near_line(*from, *to, *coords)));
+            if targets.len() < before {
+                invalidated += before - targets.len();
+                self.complete.remove(from);
+            }
+        }
+        self.sight.retain(|_, targets| !targets.is_empty());
+        self.stats.invalidated += invalidated as u64;
+        invalidated
+    }
+}
+
+/// Could the tile be on one of the sight lines between both ends, see
+/// [Coordinates::strict_line_crossing].
+fn near_line(from: Coordinates, to: Coordinates, coords: Coordinates) -> bool {
+    // Sight lines stay within one tile of the line between both centers, and tiles along
+    // that line are never more than one step out of the way.
+    from.distance(coords) + coords.distance(to) <= from.distance(to) + 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::coordinates::Crossing;
+    use crate::board::occupant::OccupantKind;
+    use crate::board::outline::Outline;
+    use crate::board::tile::TileKind;
+
+    fn enemies(occupant: &Occupant) -> bool {
+        occupant.kind == OccupantKind::Enemy
+    }
+
+    fn open_board() -> Board {
+        Board::from_outline(&Outline::Rectangle {
+            width: 9,
+            height: 9,
+        })
+    }
+
+    #[test]
+    fn sight_lines_stay_near() {
+        let origin = Coordinates { q: 0, r: 0 };
+        for to in origin.range(6) {
+            for line in origin.sight_lines(to, |_| false) {
+                for crossing in line {
+                    if let Crossing::Tile { coords, .. } = crossing {
+                        assert!(near_line(origin, to, coords), "{} {}", to, coords);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn invalidation() {
+        let mut board = open_board();
+        board.set(Coordinates::from_offset(2, 2), TileKind::Rock);
+        board.set(Coordinates::from_offset(6, 5), TileKind::Rock);
+        board.drain_events();
+        let mut cache = VisibilityCache::new(enemies);
+        let tiles = board.sorted_coordinates();
+        let from = Coordinates::from_offset(4, 4);
+
+        let seen = cache.visible_from(&board, from);
+        assert_eq!(seen, board.field_of_view(from, None, enemies));
+        assert_eq!(cache.stats().misses, 1);
+        for to in tiles.iter() {
+            assert_eq!(
+                cache.line_of_sight(&board, *to, from),
+                board.line_of_sight(*to, from, enemies)
+            );
+        }
+        assert_eq!(cache.stats().hits, tiles.len() as u64);
+        assert_eq!(cache.visible_from(&board, from), seen);
+
+        // Non-blocking changes keep everything.
+        board
+            .place(Coordinates::from_offset(0, 0), OccupantKind::Dwarf)
+            .unwrap();
+        assert_eq!(cache.handle(&board.drain_events()), 0);
+
+        // Filling a tile only forgets answers whose lines may cross it.
+        let filled = Coordinates::from_offset(5, 3);
+        let known = cache.len();
+        board.fill(filled);
+        let invalidated = cache.handle(&board.drain_events());
+        assert!(invalidated > 0 && invalidated < known);
+        assert_eq!(cache.stats().invalidated, invalidated as u64);
+
+        // Whatever is left is still right, and the rest is computed again.
+        assert_eq!(
+            cache.visible_from(&board, from),
+            board.field_of_view(from, None, enemies)
+        );
+        for to in tiles.iter() {
+            assert_eq!(
+                cache.line_of_sight(&board, from, *to),
+                board.line_of_sight(from, *to, enemies)
+            );
+        }
+        board.free(filled);
+        cache.handle(&board.drain_events());
+        assert!(cache.stats().hit_rate() > 0.5);
+    }
+
+    #[test]
+    fn replaced_board() {
+        let mut board = open_board();
+        let mut cache = VisibilityCache::new(enemies);
+        let from = Coordinates::from_offset(0, 4);
+        let to = Coordinates::from_offset(8, 4);
+        assert!(cache.line_of_sight(&board, from, to));
+
+        // Same outline, with a wall across the middle. Replacing a board emits no events.
+        board = open_board();
+        for y in 0..9 {
+            board.set(Coordinates::from_offset(4, y), TileKind::Rock);
+        }
+        board.drain_events();
+        cache.clear();
+        assert_eq!(cache.stats().invalidated, 1);
+
+        let misses = cache.stats().misses;
+        assert!(!cache.line_of_sight(&board, from, to));
+        assert_eq!(cache.stats().misses, misses + 1);
+        assert_eq!(
+            cache.visible_from(&board, from),
+            board.field_of_view(from, None, enemies)
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use super::board::Board;
+use super::coordinates::Coordinates;
+use super::direction::Direction;
+use super::layout::Layout;
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+/// What a [Zone] is used for in a mission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ZoneRole {
+    /// Where the drop pod lands and dwarves start.
+    DropPod,
+    /// Holes enemies crawl out of.
+    SpawnHole,
+    /// Room holding an objective.
+    Objective,
+    /// Where dwarves must be when the mission ends.
+    Extraction,
+    /// Anything else scenarios want to refer to.
+    Other,
+}
+
+impl ZoneRole {
+    /// Color of the zone's outline.
+    pub fn color(self) -> Color {
+        match self {
+            Self::DropPod => Color::RGB(40, 90, 200),
+            Self::SpawnHole => Color::RGB(200, 40, 40),
+            Self::Objective => Color::RGB(150, 40, 200),
+            Self::Extraction => Color::RGB(40, 170, 60),
+            Self::Other => Color::RGB(60, 60, 60),
+        }
+    }
+}
+
+/// Named group of tiles, so scenarios can refer to areas by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ZoneParts")]
+pub struct Zone {
+    pub name: String,
+    pub role: ZoneRole,
+    /// Tiles of the zone, sorted row by row, without duplicates.
+    tiles: Vec<Coordinates>,
+}
+
+/// Saved form of a [Zone], normalized when loading.
+#[derive(Deserialize)]
+struct ZoneParts {
+    name: String,
+    role: ZoneRole,
+    tiles: Vec<Coordinates>,
+}
+
+impl From<ZoneParts> for Zone {
+    fn from(parts: ZoneParts) -> Self {
+        Zone::new(&parts.name, parts.role, &parts.tiles)
+    }
+}
+
+impl Zone {
+    pub fn new(name: &str, role: ZoneRole, tiles: &[Coordinates]) -> Self {
+        let mut tiles = tiles.to_vec();
+        tiles.sort_by_key(|coords| (coords.r, coords.q));
+        tiles.dedup();
+        Zone {
+            name: name.to_string(),
+            role,
+            tiles,
+        }
+    }
+
+    pub fn contains(&self, coords: Coordinates) -> bool {
+        self.tiles
+            .binary_search_by_key(&(coords.r, coords.q), |tile| (tile.r, tile.q))
+            .is_ok()
+    }
+
+    /// Every tile of the zone, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.tiles.iter().copied()
+    }
+
+    /// Draw the border of the zone: every side of its tiles that doesn't touch another tile of
+    /// the zone.
+    pub fn draw<T>(&self, canvas: &mut Canvas<T>, layout: &Layout)
+    where
+        T: RenderTarget,
+    {
+        for coords in self.iter() {
+            for direction in Direction::ALL {
+                if self.contains(coords + direction) {
+                    continue;
+                }
+                let (from, to) = layout.side_corners(coords, direction);
+                canvas
+                    .thick_line(
+                        from.x as i16,
+                        from.y as i16,
+                        to.x as i16,
+                        to.y as i16,
+                        3,
+                        self.role.color(),
+                    )
+                    .unwrap();
+            }
+        }
+    }
+}
+
+impl Board {
+    /// Add a zone, replacing any zone with the same name.
+    pub fn define_zone(&mut self, zone: Zone) {
+        match self.zones.iter_mut().find(|other| other.name == zone.name) {
+            Some(other) => *other = zone,
+            None => self.zones.push(zone),
+        }
+    }
+
+    /// Take a zone off the board, returning it.
+    pub fn remove_zone(&mut self, name: &str) -> Option<Zone> {
+        let index = self.zones.iter().position(|zone| zone.name == name)?;
+        Some(self.zones.remove(index))
+    }
+
+    pub fn zone(&self, name: &str) -> Option<&Zone> {
+        self.zones.iter().find(|zone| zone.name == name)
+    }
+
+    /// Every zone containing the tile, in the order they were defined.
+    pub fn zones_at(&self, coords: Coordinates) -> Vec<&Zone> {
+        self.zones
+            .iter()
+            .filter(|zone| zone.contains(coords))
+            .collect()
+    }
+
+    /// Every zone with this role, in the order they were defined.
+    pub fn zones_with(&self, role: ZoneRole) -> impl Iterator<Item = &Zone> + '_ {
+        self.zones.iter().filter(move |zone| zone.role == role)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::outline::Outline;
+
+    #[test]
+    fn zones() {
+        let mut board = Board::from_outline(&Outline::Rectangle {
+            width: 6,
+            height: 6,
+        });
+        let a = Coordinates::from_offset(1, 1);
+        let b = Coordinates::from_offset(2, 1);
+        let c = Coordinates::from_offset(4, 4);
+
+        board.define_zone(Zone::new("landing", ZoneRole::DropPod, &[b, a, b]));
+        board.define_zone(Zone::new("exit", ZoneRole::Extraction, &[b, c]));
+
+        let landing = board.zone("landing").unwrap();
+        assert_eq!(landing.iter().collect::<Vec<_>>(), vec![a, b]);
+        assert!(landing.contains(a));
+        assert!(!landing.contains(c));
+
+        let names = |zones: Vec<&Zone>| {
+            zones
+                .iter()
+                .map(|zone| zone.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(board.zones_at(b)), vec!["landing", "exit"]);
+        assert_eq!(names(board.zones_at(c)), vec!["exit"]);
+        assert!(board.zones_at(Coordinates::from_offset(0, 0)).is_empty());
+        assert_eq!(board.zones_with(ZoneRole::Extraction).count(), 1);
+
+        // Same name replaces the zone.
+        board.define_zone(Zone::new("landing", ZoneRole::DropPod, &[c]));
+        assert_eq!(board.zones.len(), 2);
+        assert_eq!(names(board.zones_at(a)), Vec::<String>::new());
+
+        assert!(board.remove_zone("exit").is_some());
+        assert!(board.remove_zone("exit").is_none());
+        assert_eq!(board.zone("landing").unwrap().iter().count(), 1);
+    }
+
+    #[test]
+    fn normalized_on_load() {
+        let zone: Zone = serde_json::from_str(
+            r#"{"name": "pit", "role": "Other", "tiles": [
+                {"q": 3, "r": 1}, {"q": 0, "r": 0}, {"q": 3, "r": 1}, {"q": 1, "r": 0}
+            ]}"#,
+        )
+        .unwrap();
+        let tiles = [
+            Coordinates { q: 0, r: 0 },
+            Coordinates { q: 1, r: 0 },
+            Coordinates { q: 3, r: 1 },
+        ];
+        assert_eq!(zone.iter().collect::<Vec<_>>(), tiles);
+        assert!(tiles.iter().all(|coords| zone.contains(*coords)));
+        assert_eq!(zone, Zone::new("pit", ZoneRole::Other, &tiles));
+    }
+}
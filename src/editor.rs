@@ -0,0 +1,545 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::board::ascii::ParseError;
+use crate::board::board::Board;
+use crate::board::coordinates::Coordinates;
+use crate::board::occupant::OccupantKind;
+use crate::board::save::{self, SaveError};
+use crate::board::tile::{Tile, TileKind};
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+
+/// Largest brush radius, in tiles.
+pub const MAX_BRUSH_SIZE: i32 = 3;
+
+/// Spawn points the editor can place, in palette order.
+const SPAWNS: [OccupantKind; 4] = [
+    OccupantKind::Dwarf,
+    OccupantKind::Enemy,
+    OccupantKind::Item,
+    OccupantKind::Objective,
+];
+
+/// Keys picking each terrain, in [TileKind::ALL] order.
+const TERRAIN_KEYS: [Keycode; 8] = [
+    Keycode::Num1,
+    Keycode::Num2,
+    Keycode::Num3,
+    Keycode::Num4,
+    Keycode::Num5,
+    Keycode::Num6,
+    Keycode::Num7,
+    Keycode::Num8,
+];
+
+/// What painting puts on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Brush {
+    Terrain(TileKind),
+    /// Spawn point, on [Floor][TileKind::Floor].
+    Spawn(OccupantKind),
+    /// Removes spawn points.
+    Eraser,
+}
+
+/// Everything the editor can change on a tile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    tile: Tile,
+    spawn: Option<OccupantKind>,
+}
+
+impl Cell {
+    fn read(board: &Board, coords: Coordinates) -> Option<Self> {
+        Some(Cell {
+            tile: *board.get(coords)?,
+            spawn: board.spawns.get(&coords).copied(),
+        })
+    }
+
+    fn write(self, board: &mut Board, coords: Coordinates) {
+        board.set(coords, self.tile.kind);
+        board.set_height(coords, self.tile.height);
+        match self.spawn {
+            Some(kind) => board.spawns.insert(coords, kind),
+            None => board.spawns.remove(&coords),
+        };
+    }
+}
+
+/// Tiles changed by a single action, with what they were before and after.
+#[derive(Debug, Clone, PartialEq)]
+struct Stroke(Vec<(Coordinates, Cell, Cell)>);
+
+/// Why a map file could not be read.
+#[derive(Debug)]
+pub enum MapFileError {
+    Save(SaveError),
+    /// Text maps, see [ascii][crate::board::ascii].
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for MapFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Save(error) => write!(f, "{}", error),
+            Self::Parse(error) => write!(f, "invalid map: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for MapFileError {}
+
+impl From<SaveError> for MapFileError {
+    fn from(error: SaveError) -> Self {
+        Self::Save(error)
+    }
+}
+
+impl From<std::io::Error> for MapFileError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Save(SaveError::Io(error))
+    }
+}
+
+impl From<ParseError> for MapFileError {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+/// Read a map: a [save][crate::board::save] if the file ends with `.json`, a
+/// [text map][crate::board::ascii] otherwise.
+pub fn load(path: &Path) -> Result<Board, MapFileError> {
+    if is_save(path) {
+        Ok(save::read(File::open(path)?)?)
+    } else {
+        Ok(std::fs::read_to_string(path)?.parse()?)
+    }
+}
+
+/// Write a map in the format [load] expects for this path.
+/// Text maps only keep terrain and spawn points.
+pub fn store(board: &Board, path: &Path) -> Result<(), MapFileError> {
+    if is_save(path) {
+        save::write(board, File::create(path)?)?;
+    } else {
+        std::fs::write(path, board.to_string())?;
+    }
+    Ok(())
+}
+
+fn is_save(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+/// What [Editor::handle] did with an event.
+#[derive(Debug)]
+pub enum Handled {
+    /// Not an editor event.
+    Ignored,
+    /// The brush or the board changed.
+    Used,
+    /// The board was written to [path][Editor::path].
+    Saved,
+    /// The board was replaced by the map at [path][Editor::path]: anything derived from the old
+    /// board is stale.
+    Loaded,
+    /// The board could not be written, or read. It is unchanged.
+    Failed(MapFileError),
+}
+
+/// Map editor: paints terrain and spawn points on a board, and keeps the history to undo it.
+#[derive(Debug, Clone)]
+pub struct Editor {
+    pub brush: Brush,
+    /// Radius of the brush: 0 paints a single tile.
+    pub size: i32,
+    /// Where the map is saved and loaded.
+    pub path: PathBuf,
+    undo: Vec<Stroke>,
+    redo: Vec<Stroke>,
+}
+
+impl Editor {
+    pub fn new(path: PathBuf) -> Self {
+        Editor {
+            brush: Brush::Terrain(TileKind::Rock),
+            size: 0,
+            path,
+            undo: vec![],
+            redo: vec![],
+        }
+    }
+
+    /// What the brush makes of a tile.
+    fn apply(&self, cell: Cell) -> Cell {
+        match self.brush {
+            Brush::Terrain(kind) => Cell {
+                tile: Tile { kind, ..cell.tile },
+                // Spawn points only stand on floor.
+                spawn: cell.spawn.filter(|_| kind == TileKind::Floor),
+            },
+            Brush::Spawn(kind) => Cell {
+                tile: Tile {
+                    kind: TileKind::Floor,
+                    ..cell.tile
+                },
+                spawn: Some(kind),
+            },
+            Brush::Eraser => Cell {
+                spawn: None,
+                ..cell
+            },
+        }
+    }
+
+    /// Apply the brush to every tile, as a single action. Returns how many tiles changed.
+    fn stroke(&mut self, board: &mut Board, tiles: impl IntoIterator<Item = Coordinates>) -> usize {
+        let mut changes: Vec<(Coordinates, Cell, Cell)> = vec![];
+        for coords in tiles {
+            if let Some(before) = Cell::read(board, coords) {
+                let after = self.apply(before);
+                if after != before {
+                    after.write(board, coords);
+                    changes.push((coords, before, after));
+                }
+            }
+        }
+
+        let changed = changes.len();
+        if changed > 0 {
+            self.undo.push(Stroke(changes));
+            self.redo.clear();
+        }
+        changed
+    }
+
+    /// Paint every tile at most [size][Editor::size] tiles away from `center`.
+    pub fn paint(&mut self, board: &mut Board, center: Coordinates) -> usize {
+        self.stroke(board, center.range(self.size as u32).collect::<Vec<_>>())
+    }
+
+    /// Paint every tile connected to `start` and made of the same terrain.
+    pub fn flood_fill(&mut self, board: &mut Board, start: Coordinates) -> usize {
+        let kind = match board.get(start) {
+            Some(tile) => tile.kind,
+            None => return 0,
+        };
+
+        let mut seen: HashSet<Coordinates> = HashSet::from([start]);
+        let mut frontier: VecDeque<Coordinates> = VecDeque::from([start]);
+        let mut tiles: Vec<Coordinates> = vec![];
+        while let Some(current) = frontier.pop_front() {
+            tiles.push(current);
+            for next in board.neighbours(current) {
+                if board.tiles[&next].kind == kind && seen.insert(next) {
+                    frontier.push_back(next);
+                }
+            }
+        }
+        self.stroke(board, tiles)
+    }
+
+    /// Revert the last action. Returns false if there is nothing to undo.
+    pub fn undo(&mut self, board: &mut Board) -> bool {
+        let Some(stroke) = self.undo.pop() else {
+            return false;
+        };
+        for (coords, before, _) in stroke.0.iter().rev() {
+            before.write(board, *coords);
+        }
+        self.redo.push(stroke);
+        true
+    }
+
+    /// Apply the last undone action again. Returns false if there is nothing to redo.
+    pub fn redo(&mut self, board: &mut Board) -> bool {
+        let Some(stroke) = self.redo.pop() else {
+            return false;
+        };
+        for (coords, _, after) in stroke.0.iter() {
+            after.write(board, *coords);
+        }
+        self.undo.push(stroke);
+        true
+    }
+
+    /// Write the board to [path][Editor::path].
+    pub fn save(&self, board: &Board) -> Result<(), MapFileError> {
+        store(board, &self.path)
+    }
+
+    /// Read the board at [path][Editor::path]. History is lost, as it belongs to the old board.
+    pub fn load(&mut self) -> Result<Board, MapFileError> {
+        let board = load(&self.path)?;
+        self.undo.clear();
+        self.redo.clear();
+        Ok(board)
+    }
+
+    /// Handle an editor shortcut or a click.
+    ///
+    /// Number keys pick the terrain, `9` cycles through spawn points, `0` is the eraser,
+    /// brackets change the brush size, `F` flood fills, and Ctrl + `Z`, `Y`, `S`, `O` undo, redo,
+    /// save and load.
+    pub fn handle(&mut self, event: &Event, board: &mut Board, mouse: Coordinates) -> Handled {
+        match event {
+            Event::MouseButtonDown { .. } => {
+                self.paint(board, mouse);
+            }
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                ..
+            } => {
+                let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+                match (*keycode, ctrl) {
+                    (Keycode::Z, true) => {
+                        self.undo(board);
+                    }
+                    (Keycode::Y, true) => {
+                        self.redo(board);
+                    }
+                    (Keycode::S, true) => {
+                        return match self.save(board) {
+                            Ok(()) => Handled::Saved,
+                            Err(error) => Handled::Failed(error),
+                        };
+                    }
+                    (Keycode::O, true) => {
+                        return match self.load() {
+                            Ok(loaded) => {
+                                *board = loaded;
+                                Handled::Loaded
+                            }
+                            Err(error) => Handled::Failed(error),
+                        };
+                    }
+                    (Keycode::F, false) => {
+                        self.flood_fill(board, mouse);
+                    }
+                    (Keycode::LeftBracket, false) => self.size = (self.size - 1).max(0),
+                    (Keycode::RightBracket, false) => {
+                        self.size = (self.size + 1).min(MAX_BRUSH_SIZE)
+                    }
+                    (Keycode::Num0, false) => self.brush = Brush::Eraser,
+                    (Keycode::Num9, false) => {
+                        let next = match self.brush {
+                            Brush::Spawn(kind) => {
+                                let index = SPAWNS.iter().position(|spawn| *spawn == kind);
+                                SPAWNS[index.map_or(0, |i| (i + 1) % SPAWNS.len())]
+                            }
+                            _ => SPAWNS[0],
+                        };
+                        self.brush = Brush::Spawn(next);
+                    }
+                    (keycode, false) => match TERRAIN_KEYS.iter().position(|key| *key == keycode) {
+                        Some(index) => self.brush = Brush::Terrain(TileKind::ALL[index]),
+                        None => return Handled::Ignored,
+                    },
+                    _ => return Handled::Ignored,
+                }
+            }
+            _ => return Handled::Ignored,
+        }
+        Handled::Used
+    }
+
+    /// Draw the palette at `(x, y)`: every terrain, then every spawn point. The brush is framed.
+    pub fn draw_palette<T>(&self, canvas: &mut Canvas<T>, x: i32, y: i32)
+    where
+        T: RenderTarget,
+    {
+        const SWATCH: u32 = 24;
+        let brushes = TileKind::ALL
+            .iter()
+            .map(|kind| (Brush::Terrain(*kind), kind.color()))
+            .chain(
+                SPAWNS
+                    .iter()
+                    .map(|kind| (Brush::Spawn(*kind), kind.color())),
+            )
+            .chain([(Brush::Eraser, Color::RGB(255, 255, 255))]);
+
+        for (i, (brush, color)) in brushes.enumerate() {
+            let swatch = Rect::new(x + (i as i32) * (SWATCH as i32 + 4), y, SWATCH, SWATCH);
+            canvas.set_draw_color(color);
+            canvas.fill_rect(swatch).unwrap();
+            canvas.set_draw_color(Color::RGB(0, 20, 0));
+            canvas.draw_rect(swatch).unwrap();
+            if brush == self.brush {
+                let frame = Rect::new(swatch.x() - 2, swatch.y() - 2, SWATCH + 4, SWATCH + 4);
+                canvas.draw_rect(frame).unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::outline::Outline;
+
+    fn open_board() -> Board {
+        Board::from_outline(&Outline::Rectangle {
+            width: 9,
+            height: 9,
+        })
+    }
+
+    #[test]
+    fn paint() {
+        let mut board = open_board();
+        let mut editor = Editor::new(PathBuf::new());
+        let center = Coordinates::from_offset(4, 4);
+
+        editor.brush = Brush::Terrain(TileKind::Water);
+        assert_eq!(editor.paint(&mut board, center), 1);
+        editor.size = 2;
+        // Radius 2 covers 19 tiles, one of them already water.
+        assert_eq!(editor.paint(&mut board, center), 18);
+        // Nothing left to change.
+        assert_eq!(editor.paint(&mut board, center), 0);
+
+        editor.brush = Brush::Spawn(OccupantKind::Objective);
+        editor.size = 0;
+        editor.paint(&mut board, center);
+        assert_eq!(board.get(center).unwrap().kind, TileKind::Floor);
+        assert_eq!(board.spawns[&center], OccupantKind::Objective);
+
+        editor.brush = Brush::Terrain(TileKind::Rock);
+        editor.paint(&mut board, center);
+        assert!(board.spawns.is_empty());
+    }
+
+    #[test]
+    fn flood_fill() {
+        let mut board: Board = "\
+. . # . .
+ . # . . .
+. # . # .
+"
+        .parse()
+        .unwrap();
+        let mut editor = Editor::new(PathBuf::new());
+        editor.brush = Brush::Terrain(TileKind::Lava);
+
+        // The wall splits the floor in two.
+        assert_eq!(
+            editor.flood_fill(&mut board, Coordinates::from_offset(0, 0)),
+            4
+        );
+        assert_eq!(
+            board.get(Coordinates::from_offset(4, 0)).unwrap().kind,
+            TileKind::Floor
+        );
+        assert_eq!(
+            editor.flood_fill(&mut board, Coordinates::from_offset(9, 9)),
+            0
+        );
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut board = open_board();
+        let original = board.clone();
+        let mut editor = Editor::new(PathBuf::new());
+        let center = Coordinates::from_offset(4, 4);
+
+        editor.size = 1;
+        editor.paint(&mut board, center);
+        let painted = board.clone();
+        editor.brush = Brush::Spawn(OccupantKind::Dwarf);
+        editor.paint(&mut board, center);
+
+        assert!(editor.undo(&mut board));
+        assert_eq!(board, painted);
+        assert!(editor.undo(&mut board));
+        assert_eq!(board, original);
+        assert!(!editor.undo(&mut board));
+
+        assert!(editor.redo(&mut board));
+        assert_eq!(board, painted);
+
+        // A new action forgets what was undone.
+        editor.brush = Brush::Eraser;
+        editor.flood_fill(&mut board, center);
+        editor.brush = Brush::Terrain(TileKind::Pit);
+        editor.paint(&mut board, Coordinates::from_offset(0, 0));
+        assert!(!editor.redo(&mut board));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = std::env::temp_dir();
+        let mut board = open_board();
+        board.set_height(Coordinates::from_offset(1, 1), 2);
+
+        for name in ["editor_test.json", "editor_test.txt"] {
+            let mut editor = Editor::new(dir.join(name));
+            editor.brush = Brush::Spawn(OccupantKind::Enemy);
+            editor.paint(&mut board, Coordinates::from_offset(3, 3));
+            editor.save(&board).unwrap();
+
+            let loaded = editor.load().unwrap();
+            assert_eq!(loaded.spawns, board.spawns);
+            assert_eq!(loaded.tiles.keys().count(), board.tiles.keys().count());
+            std::fs::remove_file(&editor.path).unwrap();
+        }
+        // Only saves keep heights.
+        let mut editor = Editor::new(dir.join("editor_test.json"));
+        editor.save(&board).unwrap();
+        assert_eq!(editor.load().unwrap(), board);
+        std::fs::remove_file(&editor.path).unwrap();
+
+        assert!(matches!(
+            Editor::new(dir.join("missing.json")).load(),
+            Err(MapFileError::Save(SaveError::Io(_)))
+        ));
+    }
+
+    #[test]
+    fn shortcuts() {
+        let ctrl = |keycode| Event::KeyDown {
+            timestamp: 0,
+            window_id: 0,
+            keycode: Some(keycode),
+            scancode: None,
+            keymod: Mod::LCTRLMOD,
+            repeat: false,
+        };
+        let mut board = open_board();
+        let mouse = Coordinates::from_offset(4, 4);
+        let mut editor = Editor::new(std::env::temp_dir().join("editor_shortcuts.txt"));
+
+        assert!(matches!(
+            editor.handle(&ctrl(Keycode::O), &mut board, mouse),
+            Handled::Failed(MapFileError::Save(SaveError::Io(_)))
+        ));
+        assert_eq!(board, open_board());
+
+        let saved = board.clone();
+        assert!(matches!(
+            editor.handle(&ctrl(Keycode::S), &mut board, mouse),
+            Handled::Saved
+        ));
+        editor.paint(&mut board, mouse);
+        assert!(matches!(
+            editor.handle(&ctrl(Keycode::O), &mut board, mouse),
+            Handled::Loaded
+        ));
+        assert_eq!(board, saved);
+        assert!(matches!(
+            editor.handle(&ctrl(Keycode::Q), &mut board, mouse),
+            Handled::Ignored
+        ));
+        std::fs::remove_file(&editor.path).unwrap();
+    }
+}
@@ -0,0 +1,9 @@
+//! An unofficial, unlicensed port of DRG: The Board Game.
+
+/// Utility functions to play on a gameboard comprised of hexagonal tiles, or "hexes".
+pub mod board;
+pub mod creature;
+/// Damage computing logic.
+pub mod damage;
+/// All the [Dice][crate::dice::Dice] throwing logic.
+pub mod dice;
@@ -1,29 +1,43 @@
-/// Utility functions to play on a gameboard comprised of hexagonal tiles, or "hexes".
-mod board;
-mod creature;
-/// Damage computing logic.
-mod damage;
-/// All the [Dice][crate::dice::Dice] throwing logic.
-mod dice;
+/// In-game map editor.
+mod editor;
 /// UTF-8 + SDL2 shenanigans.
 mod utils;
 
-use board::board::Board;
+use drg::board;
+
+use board::board::{mission_seed, Board, BoardConfig};
 use board::coordinates::Coordinates;
+use board::cover::SIGHT_LINES;
 use board::direction::Direction;
+use board::edge::EdgeKind;
+use board::event::EventLog;
+use board::fog::FogOfWar;
+use board::generator::{CellularAutomata, Generator, Tunnels};
+use board::hazard::{HazardKind, HazardSystem};
+use board::layout::Layout;
+use board::occupant::{Occupant, OccupantKind, Team};
+use board::outline::Outline;
+use board::visibility::VisibilityCache;
+use editor::{Editor, Handled};
 use std::ops::Add;
 use std::path::PathBuf;
 
 extern crate sdl2;
 
-use crate::board::coordinates::FloatCoordinates;
 use crate::board::shape::Shape;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
 use sdl2::rect::Point;
 use std::time::Duration;
 
+/// Hardest terrain the dwarf can dig through, see [TileKind::hardness][board::tile::TileKind::hardness].
+const DRILL_POWER: u32 = 2;
+/// How many tiles a single drilling goes through.
+const DRILL_LENGTH: u32 = 3;
+
 fn main() {
     // SDL init.
     let sdl_context = sdl2::init().unwrap();
@@ -55,14 +69,55 @@ fn main() {
 
     // Map generation.
     let mut direction: Direction = Direction::Right;
-    let mut location: Coordinates = Coordinates { q: 0, r: 0 };
-    let mut board: Board = Board::new();
+    // An optional mission seed can be passed as first argument to replay a known cave.
+    let mut seed: u64 = match std::env::args().nth(1) {
+        Some(seed) => mission_seed(&seed),
+        None => SmallRng::from_entropy().gen(),
+    };
+    let generators: [&dyn Generator; 2] = [&CellularAutomata::default(), &Tunnels::default()];
+    let mut generator: usize = 0;
+    let outlines: [Outline; 4] = [
+        Outline::canonical(),
+        Outline::Hexagon { radius: 7 },
+        Outline::Rectangle {
+            width: 20,
+            height: 12,
+        },
+        Outline::Parallelogram {
+            width: 12,
+            height: 10,
+        },
+    ];
+    let mut config: BoardConfig = BoardConfig::default();
+    // A map file can be passed instead of a seed, as a save or as text.
+    let map: Option<PathBuf> = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .filter(|path| path.is_file());
+    let mut board: Board = match &map {
+        Some(path) => editor::load(path).unwrap_or_else(|error| panic!("{}", error)),
+        None => Board::generate(seed, &config, generators[generator]),
+    };
+    // Edits are saved to the map file, or to a new scenario.
+    let mut editor = Editor::new(map.unwrap_or_else(|| PathBuf::from("scenario.json")));
+    let mut editing: bool = false;
+    // Outcome of the last save or load, shown while editing.
+    let mut status: Option<String> = None;
+    let mut dwarf: Occupant = spawn_dwarf(&mut board);
+    let mut location: Coordinates = board
+        .occupants
+        .position(dwarf.id)
+        .expect("dwarf should be on the board");
+
+    // Where hexes are drawn, and picked with the mouse.
+    let layout = Layout::default();
 
     // Debug options.
     let mut display_pos: bool = false;
     let mut display_path: bool = false;
-    let mut line_up: bool = false;
+    let mut wide_line: bool = false;
     let mut display_los: bool = false;
+    let mut display_fog: bool = true;
 
     let mut shape: Shape = Shape {
         center: Coordinates { q: 0, r: 0 },
@@ -75,14 +130,60 @@ fn main() {
 
     let mut orientation: Direction = Direction::Right;
 
+    // Fire, gas and lava, moving forward one round at a time.
+    let mut hazards = HazardSystem::new(seed);
+
+    // What the dwarves know about the cave.
+    let mut fog = FogOfWar::new(&board);
+    let mut sight = VisibilityCache::new(is_enemy);
+
+    // Every change made to the board, by frame.
+    let mut log = EventLog::default();
+    let mut frame: u32 = 0;
+
     // Event loop.
     'running: loop {
         let mouse_state = sdl2::mouse::MouseState::new(&event_pump);
         let mouse_pos = Point::new(mouse_state.x(), mouse_state.y());
+        let mouse_hex: Coordinates = layout.pick(mouse_pos);
         canvas.set_draw_color(Color::RGB(255, 255, 255));
         canvas.clear();
 
         for event in event_pump.poll_iter() {
+            if editing {
+                match editor.handle(&event, &mut board, mouse_hex) {
+                    Handled::Ignored => (),
+                    Handled::Used => continue,
+                    Handled::Saved => {
+                        status = Some(format!("saved {}", editor.path.display()));
+                        continue;
+                    }
+                    Handled::Loaded => {
+                        // The loaded map replaces the board, and everything that came with it.
+                        status = Some(format!("loaded {}", editor.path.display()));
+                        // Ids belong to the old board: play the first dwarf saved with the new one.
+                        let saved = board
+                            .occupants
+                            .iter()
+                            .map(|(_, occupant)| occupant)
+                            .filter(|occupant| occupant.kind == OccupantKind::Dwarf)
+                            .min_by_key(|occupant| occupant.id);
+                        dwarf = saved.unwrap_or_else(|| spawn_dwarf(&mut board));
+                        fog = FogOfWar::new(&board);
+                        sight.clear();
+                        hazards = HazardSystem::new(seed);
+                        location = board
+                            .occupants
+                            .position(dwarf.id)
+                            .expect("dwarf should be on the board");
+                        continue;
+                    }
+                    Handled::Failed(error) => {
+                        status = Some(format!("{}: {}", editor.path.display(), error));
+                        continue;
+                    }
+                }
+            }
             match event {
                 Event::Quit { .. }
                 | Event::KeyDown {
@@ -90,13 +191,13 @@ fn main() {
                     ..
                 } => break 'running,
                 Event::MouseButtonDown { x, y, .. } => {
-                    let coords = Coordinates::from(Point::new(x, y));
+                    let coords = layout.pick(Point::new(x, y));
                     let tile = board.get(coords);
                     if tile.is_none() {
                         continue;
                     }
                     let tile = tile.unwrap();
-                    if tile.free {
+                    if tile.kind.is_walkable() {
                         board.fill(coords);
                     } else {
                         board.free(coords);
@@ -151,50 +252,152 @@ fn main() {
                     keycode: Some(Keycode::P),
                     ..
                 } => display_pos = false,
+                Event::KeyDown {
+                    keycode: Some(Keycode::G),
+                    ..
+                } => {
+                    // Next seed, and switch between generators.
+                    seed = seed.wrapping_add(1);
+                    generator = (generator + 1) % generators.len();
+                    if generator == 0 {
+                        // Tried every generator, switch outline.
+                        let next = outlines
+                            .iter()
+                            .position(|outline| *outline == config.outline);
+                        config.outline =
+                            outlines[next.map_or(0, |i| (i + 1) % outlines.len())].clone();
+                    }
+                    board = Board::generate(seed, &config, generators[generator]);
+                    fog = FogOfWar::default();
+                    sight.clear();
+                    hazards = HazardSystem::new(seed);
+                    dwarf = spawn_dwarf(&mut board);
+                    location = board
+                        .occupants
+                        .position(dwarf.id)
+                        .expect("dwarf should be on the board");
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::E),
+                    ..
+                } => {
+                    // Toggle an enemy under the mouse.
+                    let coords = mouse_hex;
+                    match board.occupants.unit_at(coords) {
+                        Some(unit) if unit.kind == OccupantKind::Enemy => {
+                            board.remove_occupant(unit.id);
+                        }
+                        _ => {
+                            let _ = board.place(coords, OccupantKind::Enemy);
+                        }
+                    }
+                }
                 Event::KeyDown {
                     keycode: Some(Keycode::Up),
                     ..
-                } => line_up = true,
+                } => wide_line = true,
                 Event::KeyUp {
                     keycode: Some(Keycode::Up),
                     ..
-                } => line_up = false,
+                } => wide_line = false,
                 Event::KeyDown {
                     keycode: Some(Keycode::Space),
                     ..
                 } => {
-                    let previous_loc = location;
                     let try_loc = location + direction;
-                    match board.get(try_loc) {
-                        Some(x) => {
-                            if !x.free {
-                                break;
-                            }
-                            location = try_loc;
-                        }
-                        None => (),
+                    if board.move_occupant(dwarf.id, try_loc).is_ok() {
+                        location = try_loc;
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode @ (Keycode::H | Keycode::L)),
+                    ..
+                } => {
+                    // Raise or lower the ground under the mouse.
+                    let coords = mouse_hex;
+                    if let Some(tile) = board.get(coords) {
+                        let step = if keycode == Keycode::H { 1 } else { -1 };
+                        board.set_height(coords, tile.height + step);
                     }
                 }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Tab),
+                    ..
+                } => editing = !editing,
+                Event::KeyDown {
+                    keycode: Some(Keycode::N),
+                    ..
+                } => {
+                    hazards.step(&mut board);
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode @ (Keycode::I | Keycode::O)),
+                    ..
+                } => {
+                    // Start a fire or release gas under the mouse.
+                    let kind = if keycode == Keycode::I {
+                        HazardKind::Fire
+                    } else {
+                        HazardKind::Gas
+                    };
+                    hazards.ignite(&board, mouse_hex, kind);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::V),
+                    ..
+                } => display_fog = !display_fog,
+                Event::KeyDown {
+                    keycode: Some(Keycode::X),
+                    ..
+                } => {
+                    board.drill_line(location, direction, DRILL_LENGTH, DRILL_POWER);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::B),
+                    ..
+                } => {
+                    // Blast the ghost shape under the mouse.
+                    board.carve(&shape, DRILL_POWER);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::W),
+                    ..
+                } => {
+                    // Cycle the side the dwarf faces: wall, closed door, open door, nothing.
+                    let next = match board.edge(location, direction) {
+                        None => Some(EdgeKind::Wall),
+                        Some(EdgeKind::Wall) => Some(EdgeKind::ClosedDoor),
+                        Some(EdgeKind::ClosedDoor) => Some(EdgeKind::OpenDoor),
+                        Some(EdgeKind::OpenDoor) => None,
+                    };
+                    board.set_edge(location, direction, next);
+                }
                 _ => {}
             }
         }
 
         // Draw all tiles.
-        board.draw(&mut canvas);
+        board.draw(&mut canvas, &layout);
+        hazards.draw(&mut canvas, &layout, &board);
+        fog.handle(&board, board.events.pending());
+        sight.handle(board.events.pending());
+        if display_fog {
+            fog.draw(&mut canvas, &layout, &board, Team::Dwarves);
+        }
 
         // Draw tile on mouse;
-        if let Some(_tile) = board.get(mouse_pos.into()) {
-            let line = location.line(mouse_pos.into());
+        if let Some(_tile) = board.get(mouse_hex) {
             let chosen_line: Vec<Coordinates>;
             if display_path {
-                chosen_line = board.path(location, mouse_pos.into()).unwrap_or(vec![]);
-            } else if line_up {
-                chosen_line = line.0;
+                chosen_line = board
+                    .path_with(location, mouse_hex, is_enemy)
+                    .unwrap_or(vec![]);
+            } else if wide_line {
+                chosen_line = location.thick_line(mouse_hex, 3);
             } else {
-                chosen_line = line.1;
+                chosen_line = location.supercover(mouse_hex);
             }
-            let coords: Coordinates = mouse_pos.into();
-            let pos: Point = coords.into();
+            let pos: Point = layout.hex_to_pixel(mouse_hex);
             /*
             texture
                 .render(&mut canvas, pos.offset(-30, -30))
@@ -203,7 +406,8 @@ fn main() {
             /*
             x.add_color(
                 &mut canvas,
-                mouse_pos.into(),
+                &layout,
+                mouse_hex,
                 Color {
                     r: 255,
                     g: 0,
@@ -218,48 +422,40 @@ fn main() {
                     &font,
                     &texture_creator,
                     Point::new(900, 1000),
-                    location.distance(mouse_pos.into()).to_string().as_str(),
+                    location.distance(mouse_hex).to_string().as_str(),
                 );
+                // Lines go off the board near its edges.
                 chosen_line.iter().for_each(|coord| {
-                    board.get(*coord).unwrap().add_color(
-                        &mut canvas,
-                        *coord,
-                        Color {
-                            r: 0,
-                            g: 150,
-                            b: 0,
-                            a: 70,
-                        },
-                    )
+                    if let Some(tile) = board.get(*coord) {
+                        tile.add_color(
+                            &mut canvas,
+                            &layout,
+                            *coord,
+                            Color {
+                                r: 0,
+                                g: 150,
+                                b: 0,
+                                a: 70,
+                            },
+                        )
+                    }
                 });
             }
         }
 
         if display_los {
-            let tiles = board.tiles.iter().map(|(coord, tile)| {
-                if !tile.free {
-                    return (coord, false);
-                }
-
-                let res = location.strict_line(&board, *coord, |option| {
-                    if option.is_none() {
-                        return false;
-                    }
-                    option.unwrap().free
-                });
-
-                if res {
-                    return (coord, true);
-                }
-                (coord, false)
-            });
+            let seen = sight.visible_from(&board, location);
+            let tiles = board
+                .tiles
+                .iter()
+                .map(|(coord, _)| (coord, seen.contains(&coord)));
             tiles.for_each(|(coords, visible)| {
-                let tile = board.get(*coords);
+                let tile = board.get(coords);
                 if tile.is_none() {
                     return;
                 }
                 let tile = tile.unwrap();
-                if !tile.free {
+                if tile.kind.is_opaque() {
                     return;
                 }
                 let color: Color;
@@ -268,16 +464,34 @@ fn main() {
                 } else {
                     color = Color::RGB(255, 125, 125);
                 }
-                tile.add_color(&mut canvas, *coords, color)
+                tile.add_color(&mut canvas, &layout, coords, color)
             })
         }
         // Draw current position and direction.
         let arrow = direction.to_string();
-        let mut text_center: Point = location.into();
+        let mut text_center: Point = layout.hex_to_pixel(location);
         text_center.x -= 3;
         text_center.y -= 10;
         utils::render_text(&mut canvas, &font, &texture_creator, text_center, &arrow);
-        if display_los {
+        if editing {
+            editor.draw_palette(&mut canvas, 20, 20);
+            utils::render_text(
+                &mut canvas,
+                &font,
+                &texture_creator,
+                Point::new(1200, 475),
+                &format!("mode (Tab): editor, brush size {}", editor.size),
+            );
+            if let Some(status) = &status {
+                utils::render_text(
+                    &mut canvas,
+                    &font,
+                    &texture_creator,
+                    Point::new(1200, 450),
+                    status,
+                );
+            }
+        } else if display_los {
             utils::render_text(
                 &mut canvas,
                 &font,
@@ -315,14 +529,14 @@ fn main() {
             &font,
             &texture_creator,
             Point::new(1200, 500),
-            FloatCoordinates::from(mouse_pos).to_string().as_str(),
+            layout.pixel_to_hex(mouse_pos).to_string().as_str(),
         );
         utils::render_text(
             &mut canvas,
             &font,
             &texture_creator,
             Point::new(1200, 525),
-            Coordinates::from(mouse_pos).to_string().as_str(),
+            mouse_hex.to_string().as_str(),
         );
         utils::render_text(
             &mut canvas,
@@ -332,7 +546,7 @@ fn main() {
             &format!("Player position: {}", location),
         );
 
-        shape.center = mouse_pos.into();
+        shape.center = mouse_hex;
         let cone = if shape.center.in_cone(location, orientation) {
             "in cone"
         } else {
@@ -345,9 +559,52 @@ fn main() {
             Point::new(1200, 575),
             cone,
         );
-        board.ghost_shape(shape.clone(), &mut canvas);
+        let stats = sight.stats();
+        utils::render_text(
+            &mut canvas,
+            &font,
+            &texture_creator,
+            Point::new(1200, 625),
+            &format!(
+                "Sight cache: {} hits, {} misses, {} invalidated",
+                stats.hits, stats.misses, stats.invalidated
+            ),
+        );
+        let cover = board.cover(location, mouse_hex, is_enemy);
+        utils::render_text(
+            &mut canvas,
+            &font,
+            &texture_creator,
+            Point::new(1200, 600),
+            &format!(
+                "Cover: {:?} ({}/{} lines, from {:?})",
+                cover.level, cover.clear_lines, SIGHT_LINES, cover.tiles
+            ),
+        );
+        board.ghost_shape(shape.clone(), &mut canvas, &layout);
+
+        log.record(frame, &mut board);
+        frame += 1;
 
         canvas.present();
         std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
     }
 }
+
+/// Put the player on a dwarf spawn point, or on the first walkable tile if the map has none.
+fn spawn_dwarf(board: &mut Board) -> Occupant {
+    let start = board
+        .sorted_coordinates()
+        .into_iter()
+        .filter(|coords| board.tiles[coords].kind.is_walkable())
+        .min_by_key(|coords| board.spawns.get(coords) != Some(&OccupantKind::Dwarf))
+        .expect("board should have a free tile");
+    board
+        .place(start, OccupantKind::Dwarf)
+        .expect("start tile should be free")
+}
+
+/// Enemies block movement and sight, dwarves and items don't.
+fn is_enemy(occupant: &Occupant) -> bool {
+    occupant.kind == OccupantKind::Enemy
+}